@@ -0,0 +1,114 @@
+use crate::model::{Broadcast, Linescore, LiveFeed, PersonDetail, Response, ResponseType};
+use failure::{bail, Error, ResultExt};
+use futures::AsyncReadExt;
+use http::{Request, Uri};
+use http_client::{native::NativeClient, Body, HttpClient};
+
+/// Client for the parts of the NHL stats api game feed that aren't
+/// covered by `stats_api`
+pub struct Client {
+    client: NativeClient,
+    base: String,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        let client = NativeClient::new();
+        let base = String::from("https://statsapi.web.nhl.com/api/v1");
+        Client { client, base }
+    }
+
+    fn get_url(&self, path: &str) -> http::Uri {
+        let uri = format!("{}/{}", self.base, path);
+        uri.parse::<Uri>().unwrap()
+    }
+
+    async fn get(&self, url: Uri, response_type: ResponseType) -> Result<Response, Error> {
+        let request = Request::builder()
+            .method("GET")
+            .uri(url)
+            .body(Body::empty())
+            .unwrap();
+
+        let res = self
+            .client
+            .send(request)
+            .await
+            .context("Failed to get request")?;
+
+        let mut body = res.into_body();
+        let mut bytes = Vec::new();
+        body.read_to_end(&mut bytes).await?;
+
+        let response = response_type.deserialize(&bytes);
+
+        Ok(response)
+    }
+
+    pub async fn get_linescore(&self, game_id: u64) -> Result<Linescore, Error> {
+        let url = self.get_url(&format!("game/{}/linescore", game_id));
+        let response_type = ResponseType::Linescore;
+
+        let _response = self.get(url, response_type).await?;
+
+        if let Response::Linescore(Some(response)) = _response {
+            return Ok(response);
+        }
+        bail!("Could not deserialize linescore");
+    }
+
+    pub async fn get_live_feed(&self, game_id: u64) -> Result<LiveFeed, Error> {
+        let url = self.get_url(&format!("game/{}/feed/live", game_id));
+        let response_type = ResponseType::LiveFeed;
+
+        let _response = self.get(url, response_type).await?;
+
+        if let Response::LiveFeed(Some(response)) = _response {
+            return Ok(response);
+        }
+        bail!("Could not deserialize live feed");
+    }
+
+    /// Broadcasts of a game. The live feed doesn't have them, so they come
+    /// from the game's schedule entry.
+    pub async fn get_broadcasts(&self, game_id: u64) -> Result<Vec<Broadcast>, Error> {
+        let url = self.get_url(&format!(
+            "schedule?gamePk={}&expand=schedule.broadcasts",
+            game_id
+        ));
+        let response_type = ResponseType::Broadcasts;
+
+        let _response = self.get(url, response_type).await?;
+
+        if let Response::Broadcasts(Some(response)) = _response {
+            let broadcasts = response
+                .dates
+                .into_iter()
+                .flat_map(|date| date.games)
+                .flat_map(|game| game.broadcasts)
+                .collect();
+            return Ok(broadcasts);
+        }
+        bail!("Could not deserialize broadcasts");
+    }
+
+    pub async fn get_person(&self, person_id: u32) -> Result<PersonDetail, Error> {
+        let url = self.get_url(&format!("people/{}", person_id));
+        let response_type = ResponseType::People;
+
+        let _response = self.get(url, response_type).await?;
+
+        if let Response::People(Some(response)) = _response {
+            if let Some(person) = response.people.into_iter().next() {
+                return Ok(person);
+            }
+        }
+        bail!("Could not deserialize person");
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
@@ -0,0 +1,4 @@
+mod client;
+pub use client::Client;
+
+pub mod model;
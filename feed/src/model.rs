@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveFeed {
+    pub game_pk: u64,
+    pub live_data: LiveData,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LiveData {
+    pub plays: Plays,
+    pub linescore: Linescore,
+    pub boxscore: Boxscore,
+    #[serde(default)]
+    pub decisions: Decisions,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Linescore {
+    #[serde(default)]
+    pub current_period: u8,
+    pub current_period_ordinal: Option<String>,
+    pub current_period_time_remaining: Option<String>,
+    pub teams: LinescoreTeams,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LinescoreTeams {
+    pub home: LinescoreTeam,
+    pub away: LinescoreTeam,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinescoreTeam {
+    pub team: TeamRef,
+    pub goals: u8,
+    #[serde(default)]
+    pub shots_on_goal: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TeamRef {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Plays {
+    #[serde(default)]
+    pub all_plays: Vec<Play>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Play {
+    pub result: PlayResult,
+    pub about: PlayAbout,
+    #[serde(default)]
+    pub players: Vec<PlayPlayer>,
+}
+
+impl Play {
+    /// Players involved in the play of the given type, ie. "Scorer" or "Assist"
+    pub fn players_of_type<'a>(&'a self, player_type: &'a str) -> impl Iterator<Item = &'a Person> {
+        self.players
+            .iter()
+            .filter(move |player| player.player_type == player_type)
+            .map(|player| &player.player)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayResult {
+    pub event_type_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayAbout {
+    pub event_id: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayPlayer {
+    pub player: Person,
+    pub player_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct People {
+    pub people: Vec<PersonDetail>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonDetail {
+    pub id: u32,
+    pub full_name: String,
+    pub current_team: Option<TeamRef>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Boxscore {
+    pub teams: BoxscoreTeams,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BoxscoreTeams {
+    pub home: BoxscoreTeam,
+    pub away: BoxscoreTeam,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxscoreTeam {
+    pub team: TeamRef,
+    pub team_stats: TeamStats,
+    /// Keyed by "ID" + player id, ie. "ID8478403"
+    #[serde(default)]
+    pub players: HashMap<String, BoxscorePlayer>,
+    /// Player ids of goalies that have played, starter first
+    #[serde(default)]
+    pub goalies: Vec<u32>,
+}
+
+impl BoxscoreTeam {
+    pub fn player(&self, id: u32) -> Option<&BoxscorePlayer> {
+        self.players.get(&format!("ID{}", id))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamStats {
+    pub team_skater_stats: TeamSkaterStats,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSkaterStats {
+    pub goals: u32,
+    pub shots: u32,
+    pub pim: u32,
+    pub power_play_goals: f64,
+    pub power_play_opportunities: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BoxscorePlayer {
+    pub person: Person,
+    #[serde(default)]
+    pub stats: PlayerStats,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerStats {
+    pub goalie_stats: Option<GoalieStats>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalieStats {
+    pub saves: u32,
+    pub shots: u32,
+    /// "mm:ss" played
+    #[serde(default)]
+    pub time_on_ice: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    pub id: u32,
+    pub full_name: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Decisions {
+    pub winner: Option<Person>,
+    pub loser: Option<Person>,
+    pub first_star: Option<Person>,
+    pub second_star: Option<Person>,
+    pub third_star: Option<Person>,
+}
+
+impl Decisions {
+    /// Three stars in order, once they've all been announced
+    pub fn three_stars(&self) -> Option<[&Person; 3]> {
+        match (&self.first_star, &self.second_star, &self.third_star) {
+            (Some(first), Some(second), Some(third)) => Some([first, second, third]),
+            _ => None,
+        }
+    }
+}
+
+/// TV & streaming broadcasts of a game
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Broadcast {
+    pub name: String,
+    /// Market the broadcast is for, "home", "away" or "national"
+    #[serde(rename = "type")]
+    pub market: String,
+    #[serde(default)]
+    pub language: String,
+}
+
+/// Schedule for a single game, expanded with its broadcasts
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BroadcastSchedule {
+    #[serde(default)]
+    pub dates: Vec<BroadcastDate>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BroadcastDate {
+    #[serde(default)]
+    pub games: Vec<BroadcastGame>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BroadcastGame {
+    #[serde(default)]
+    pub broadcasts: Vec<Broadcast>,
+}
+
+pub enum ResponseType {
+    Broadcasts,
+    Linescore,
+    LiveFeed,
+    People,
+}
+
+#[derive(Debug)]
+pub enum Response {
+    Broadcasts(Option<BroadcastSchedule>),
+    Linescore(Option<Linescore>),
+    LiveFeed(Option<LiveFeed>),
+    People(Option<People>),
+}
+
+impl ResponseType {
+    pub fn deserialize(&self, body: &[u8]) -> Response {
+        match self {
+            ResponseType::Broadcasts => {
+                if let Ok(deser) = serde_json::from_slice(body) {
+                    Response::Broadcasts(Some(deser))
+                } else {
+                    Response::Broadcasts(None)
+                }
+            }
+            ResponseType::Linescore => {
+                if let Ok(deser) = serde_json::from_slice(body) {
+                    Response::Linescore(Some(deser))
+                } else {
+                    Response::Linescore(None)
+                }
+            }
+            ResponseType::LiveFeed => {
+                if let Ok(deser) = serde_json::from_slice(body) {
+                    Response::LiveFeed(Some(deser))
+                } else {
+                    Response::LiveFeed(None)
+                }
+            }
+            ResponseType::People => {
+                if let Ok(deser) = serde_json::from_slice(body) {
+                    Response::People(Some(deser))
+                } else {
+                    Response::People(None)
+                }
+            }
+        }
+    }
+}
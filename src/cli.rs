@@ -1,6 +1,15 @@
-use crate::config::{generate_empty_config, AppConfig};
+use crate::{
+    config::{generate_empty_config, AppConfig, TeamRef},
+    history::HistoryFilter,
+    ics::Export,
+    schedule::Format,
+    simulate::Simulation,
+    source::{LiveSource, Recorder, Replayer, StatsSource},
+    test_notify::Recipients,
+};
+use chrono::NaiveDate;
 use failure::{bail, Error, ResultExt};
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf, rc::Rc};
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -19,19 +28,269 @@ pub struct Opt {
 pub enum Command {
     /// Run the program
     Run {
-        #[structopt(short, long, parse(from_os_str))]
-        /// Specify path to the config.yml file
-        config: PathBuf,
-        #[structopt(long = "twil-sid", env = "TWIL_ACCOUNT_SID")]
-        twil_sid: Option<String>,
-        #[structopt(long = "twil-token", env = "TWIL_AUTH_TOKEN", hide_env_values = true)]
-        twil_token: Option<String>,
-        #[structopt(long = "twil-from", env = "TWIL_FROM")]
-        /// Specify the From number for twilio, must be formatted as '+15555555'
-        twil_from: Option<String>,
+        #[structopt(flatten)]
+        opts: RunOpts,
+        #[structopt(flatten)]
+        source: SourceOpts,
+        #[structopt(long = "dry-run")]
+        /// Print notifications instead of sending them. Twilio creds aren't required
+        dry_run: bool,
     },
+    /// Run continuously, handling every game day at the configured notification time
+    Daemon(RunOpts),
+    /// Export subscribed teams' games as an iCalendar (.ics) file
+    ExportIcs(ExportIcsOpts),
     /// Generate an empty config.yml file to the current directory
     Generate,
+    /// Show notifications that have been sent
+    History(HistoryOpts),
+    /// List upcoming games for subscribed teams and who would be notified
+    Schedule(ScheduleOpts),
+    /// Serve the short highlight links set up with `short_links` in config
+    ServeLinks(ServeLinksOpts),
+    /// Run a made up game, printing notifications instead of sending them
+    Simulate(SimulateOpts),
+    /// Send a sample goal notification to check subscribers get messages
+    TestNotify(TestNotifyOpts),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct RunOpts {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Specify path to the config.yml file
+    config: PathBuf,
+    #[structopt(long = "twil-sid", env = "TWIL_ACCOUNT_SID")]
+    twil_sid: Option<String>,
+    #[structopt(long = "twil-token", env = "TWIL_AUTH_TOKEN", hide_env_values = true)]
+    twil_token: Option<String>,
+    #[structopt(long = "twil-from", env = "TWIL_FROM")]
+    /// Specify the From number for twilio, must be formatted as '+15555555'
+    twil_from: Option<String>,
+}
+
+impl RunOpts {
+    /// Config without Twilio creds, for when nothing is really sent
+    fn app_config_without_twilio(self) -> Result<AppConfig, Error> {
+        let app_config = AppConfig::new(
+            self.config,
+            self.twil_sid.unwrap_or_default(),
+            self.twil_token.unwrap_or_default(),
+            self.twil_from.unwrap_or_default(),
+        )
+        .context("Failed to get config")?;
+
+        Ok(app_config)
+    }
+
+    fn app_config(self) -> Result<AppConfig, Error> {
+        if self.twil_sid.is_none() || self.twil_token.is_none() || self.twil_from.is_none() {
+            bail!("TWIL_ACCOUNT_SID, TWIL_AUTH_TOKEN & TWIL_FROM env variables must be set, or passed via --twil-sid, --twil-token & --twil-from");
+        }
+        let twil_sid = self.twil_sid.unwrap();
+        let twil_token = self.twil_token.unwrap();
+        let twil_from = self.twil_from.unwrap();
+
+        let app_config = AppConfig::new(self.config, twil_sid, twil_token, twil_from)
+            .context("Failed to get config")?;
+
+        Ok(app_config)
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct SourceOpts {
+    #[structopt(long, parse(from_os_str), conflicts_with = "replay")]
+    /// Save every NHL api response to this file, so the games can be replayed later
+    record: Option<PathBuf>,
+    #[structopt(long, parse(from_os_str))]
+    /// Run games from a file saved with --record instead of the NHL api. Implies --dry-run
+    replay: Option<PathBuf>,
+    #[structopt(long, default_value = "1", requires = "replay")]
+    /// How many times faster than real time to replay
+    speed: f64,
+}
+
+impl SourceOpts {
+    fn source(self) -> Result<Rc<dyn StatsSource>, Error> {
+        if let Some(path) = self.replay {
+            return Ok(Rc::new(Replayer::new(&path, self.speed)?));
+        }
+        if let Some(path) = self.record {
+            return Ok(Rc::new(Recorder::new(LiveSource::new(), &path)?));
+        }
+        Ok(Rc::new(LiveSource::new()))
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct HistoryOpts {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Specify path to the config.yml file
+    config: PathBuf,
+    #[structopt(long)]
+    /// Only show notifications sent on this local date, formatted as 'YYYY-MM-DD'
+    date: Option<NaiveDate>,
+    #[structopt(long)]
+    /// Only show notifications for this game id
+    game: Option<u64>,
+    #[structopt(long)]
+    /// Only show notifications sent to this number, formatted as '+15555555'
+    number: Option<String>,
+}
+
+impl HistoryOpts {
+    fn history(self) -> Result<(AppConfig, HistoryFilter), Error> {
+        // Twilio isn't used to read history, so creds aren't required
+        let app_config = AppConfig::new(self.config, String::new(), String::new(), String::new())
+            .context("Failed to get config")?;
+
+        let filter = HistoryFilter {
+            date: self.date,
+            game_id: self.game,
+            number: self.number,
+        };
+
+        Ok((app_config, filter))
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ScheduleOpts {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Specify path to the config.yml file
+    config: PathBuf,
+    #[structopt(long, default_value = "7")]
+    /// How many days ahead to list games for
+    days: u32,
+    #[structopt(long, default_value = "table", possible_values = &["table", "json"])]
+    /// Print as a table or as JSON
+    format: Format,
+}
+
+impl ScheduleOpts {
+    fn schedule(self) -> Result<(AppConfig, u32, Format), Error> {
+        // Nothing is sent, so Twilio creds aren't required
+        let app_config = AppConfig::new(self.config, String::new(), String::new(), String::new())
+            .context("Failed to get config")?;
+
+        Ok((app_config, self.days, self.format))
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ServeLinksOpts {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Specify path to the config.yml file
+    config: PathBuf,
+}
+
+impl ServeLinksOpts {
+    fn app_config(self) -> Result<AppConfig, Error> {
+        // Nothing is sent, so Twilio creds aren't required
+        let app_config = AppConfig::new(self.config, String::new(), String::new(), String::new())
+            .context("Failed to get config")?;
+
+        Ok(app_config)
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ExportIcsOpts {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Specify path to the config.yml file
+    config: PathBuf,
+    #[structopt(long, default_value = "30")]
+    /// How many days ahead to include games for
+    days: u32,
+    #[structopt(short, long, parse(from_os_str), conflicts_with = "serve")]
+    /// Write the calendar to this file instead of stdout
+    output: Option<PathBuf>,
+    #[structopt(long)]
+    /// Serve the calendar over HTTP on this address, ie. '127.0.0.1:8080'
+    serve: Option<SocketAddr>,
+}
+
+impl ExportIcsOpts {
+    fn export(self) -> Result<(AppConfig, u32, Export), Error> {
+        // Nothing is sent, so Twilio creds aren't required
+        let app_config = AppConfig::new(self.config, String::new(), String::new(), String::new())
+            .context("Failed to get config")?;
+
+        let export = match (self.output, self.serve) {
+            (Some(path), _) => Export::File(path),
+            (None, Some(addr)) => Export::Serve(addr),
+            (None, None) => Export::Print,
+        };
+
+        Ok((app_config, self.days, export))
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct SimulateOpts {
+    #[structopt(short, long, parse(from_os_str))]
+    /// Specify path to the config.yml file
+    config: PathBuf,
+    #[structopt(long)]
+    /// Home team id, abbreviation or name. Defaults to the first team subscribed to
+    home: Option<String>,
+    #[structopt(long)]
+    /// Away team id, abbreviation or name
+    away: Option<String>,
+    #[structopt(long, default_value = "60")]
+    /// How many times faster than real time to play the game
+    speed: f64,
+}
+
+impl SimulateOpts {
+    fn simulation(self) -> Result<(AppConfig, Simulation), Error> {
+        // Nothing is sent, so Twilio creds aren't required
+        let app_config = AppConfig::new(self.config, String::new(), String::new(), String::new())
+            .context("Failed to get config")?;
+
+        let simulation = Simulation {
+            home: self.home.map(team_ref),
+            away: self.away.map(team_ref),
+            speed: self.speed,
+        };
+
+        Ok((app_config, simulation))
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct TestNotifyOpts {
+    #[structopt(flatten)]
+    opts: RunOpts,
+    #[structopt(long)]
+    /// Send to this number, formatted as '+15555555'
+    number: Option<String>,
+    #[structopt(long)]
+    /// Send to everyone subscribed to this team id, abbreviation or name
+    team: Option<String>,
+    #[structopt(long)]
+    /// Send to everyone in config
+    all: bool,
+}
+
+impl TestNotifyOpts {
+    fn test_notify(self) -> Result<(AppConfig, Recipients), Error> {
+        let recipients = match (self.number, self.team, self.all) {
+            (Some(number), None, false) => Recipients::Number(number),
+            (None, Some(team), false) => Recipients::Team(team_ref(team)),
+            (None, None, true) => Recipients::All,
+            _ => bail!("Exactly one of --number, --team or --all must be given"),
+        };
+
+        Ok((self.opts.app_config()?, recipients))
+    }
+}
+
+fn team_ref(team: String) -> TeamRef {
+    match team.parse() {
+        Ok(id) => TeamRef::Id(id),
+        Err(_) => TeamRef::Name(team),
+    }
 }
 
 pub fn parse_opts() -> Result<CliStatus, Error> {
@@ -45,27 +304,53 @@ pub fn parse_opts() -> Result<CliStatus, Error> {
             Ok(CliStatus::Exit)
         }
         Command::Run {
-            config,
-            twil_sid,
-            twil_token,
-            twil_from,
+            opts,
+            source,
+            dry_run,
         } => {
-            if twil_sid.is_none() || twil_token.is_none() || twil_from.is_none() {
-                bail!("TWIL_ACCOUNT_SID, TWIL_AUTH_TOKEN & TWIL_FROM env variables must be set, or passed via --twil-sid, --twil-token & --twil-from");
+            // Replays never text real subscribers or touch the real state dir
+            if dry_run || source.replay.is_some() {
+                let app_config = opts.app_config_without_twilio()?;
+                Ok(CliStatus::DryRun(app_config, source.source()?))
+            } else {
+                let app_config = opts.app_config()?;
+                Ok(CliStatus::Run(app_config, source.source()?))
             }
-            let twil_sid = twil_sid.unwrap();
-            let twil_token = twil_token.unwrap();
-            let twil_from = twil_from.unwrap();
-
-            let app_config = AppConfig::new(config, twil_sid, twil_token, twil_from)
-                .context("Failed to get config")?;
-
-            Ok(CliStatus::Continue(app_config))
+        }
+        Command::Daemon(opts) => Ok(CliStatus::Daemon(opts.app_config()?)),
+        Command::History(opts) => {
+            let (app_config, filter) = opts.history()?;
+            Ok(CliStatus::History(app_config, filter))
+        }
+        Command::ExportIcs(opts) => {
+            let (app_config, days, export) = opts.export()?;
+            Ok(CliStatus::ExportIcs(app_config, days, export))
+        }
+        Command::Schedule(opts) => {
+            let (app_config, days, format) = opts.schedule()?;
+            Ok(CliStatus::Schedule(app_config, days, format))
+        }
+        Command::ServeLinks(opts) => Ok(CliStatus::ServeLinks(opts.app_config()?)),
+        Command::Simulate(opts) => {
+            let (app_config, simulation) = opts.simulation()?;
+            Ok(CliStatus::Simulate(app_config, simulation))
+        }
+        Command::TestNotify(opts) => {
+            let (app_config, recipients) = opts.test_notify()?;
+            Ok(CliStatus::TestNotify(app_config, recipients))
         }
     }
 }
 
 pub enum CliStatus {
-    Continue(AppConfig),
+    Run(AppConfig, Rc<dyn StatsSource>),
+    DryRun(AppConfig, Rc<dyn StatsSource>),
+    Daemon(AppConfig),
+    ExportIcs(AppConfig, u32, Export),
+    History(AppConfig, HistoryFilter),
+    Schedule(AppConfig, u32, Format),
+    ServeLinks(AppConfig),
+    Simulate(AppConfig, Simulation),
+    TestNotify(AppConfig, Recipients),
     Exit,
 }
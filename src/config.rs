@@ -1,32 +1,124 @@
-use chrono::NaiveTime;
+use crate::{
+    highlight::{self, Rendition},
+    locale::Locale,
+    template::{MessageKind, Templates},
+};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use failure::{bail, format_err, Error, ResultExt};
 use serde::Deserialize;
-use std::{collections::HashMap, env, fs, path::PathBuf};
+use stats_api::model::Team;
+use std::{collections::HashMap, env, fs, net::SocketAddr, path::PathBuf};
 
 static REFERENCE_CONF: &str = "# Populate config with your own values
 
 # The earliest time a notification will be sent that your team plays today: HH:MM:SS
 earliest_notification_time: 07:00:00
 
-# Subscriptions are declared as a team id, then a list of phone numbers
+# Where game progress is saved, so a restart mid-game doesn't notify anything
+# twice. Defaults to a `state` dir next to this config file.
+# state_dir: /var/lib/nhl-notifier/state
+
+# SQLite database every notification sent is recorded to, see `nhl-notifier
+# history`. Defaults to `history.db` next to this config file.
+# history_db: /var/lib/nhl-notifier/history.db
+
+# Message text can be changed with templates, for every subscription here or
+# for one subscription with its own `templates`. See the README for each
+# message's {placeholders}. Use {{ and }} for literal braces.
+# templates:
+#   goal: \"{scoring_team} score! {home_abbr} {score_home} - {away_abbr} {score_away}\"
+#   final: \"Final: {home_abbr} {score_home} - {away_abbr} {score_away}\"
+
+# Highlight clip renditions to send, most preferred first. Each is `highest` or
+# `lowest` bitrate, `mobile`, `hls` or a rendition's name. If none are found,
+# the highest bitrate clip is sent. Defaults to FLASH_1800K_896x504.
+# highlight_renditions: [mobile, FLASH_1800K_896x504]
+
+# Shorten highlight links to `base_url`/h/<id>, so they fit in fewer SMS
+# segments. The daemon serves the redirects on `listen`, otherwise run
+# `nhl-notifier serve-links`. Links are kept in the history database.
+# short_links:
+#   base_url: https://nhl.example.com
+#   listen: 0.0.0.0:8080
+
+# Subscriptions are declared as a team, then a list of phone numbers
 # that should receive notifications for that team.
 #
+# A team can be its id, abbreviation (VGK) or name (Vegas Golden Knights).
 # Team id's can be referenced from https://statsapi.web.nhl.com/api/v1/teams
 #
+# A subscription can instead follow a player id, for any game that player
+# is in. `player_events` can be goals, assists or both (the default).
+#
+# Player id's can be referenced from https://statsapi.web.nhl.com/api/v1/teams?expand=team.roster
+#
+# A subscription can also cover every game in the league (`league: true`),
+# a division (`division: Pacific`) or a conference (`conference: Western`).
+# Set `only_final: true` to only get final scores for those games.
+#
+# Each subscription can choose which events it gets with `events`, from:
+# preview, puck_drop, goals_for, goals_against, disallowed, scoring_change,
+# score_correction, highlights, final, recap & digest. All events are sent by
+# default. `puck_drop` has the starting goalies and where the game is on TV.
+# `disallowed` is sent once the linescore takes the goal away, or after it's
+# been missing from the game's milestones for three checks in a row.
+# `digest` is a list of your teams' games that week, sent on Mondays.
+# Set `only_if_win: true` to only get the final score when your team wins.
+#
+# `timezone` sets the IANA time zone game times are shown in, ie.
+# America/Los_Angeles, otherwise this server's time zone is used.
+# `quiet_hours` holds previews, highlights & recaps between `start` and `end`
+# until quiet hours are over. Set `action: drop` to drop them instead.
+#
+# `locale` is the language messages are sent in, en (the default) or fr.
+#
+# A number in more than one subscription must have the same locale and
+# templates in each, and the same timezone and quiet_hours in each team,
+# league, division or conference subscription.
+#
+# Set `highlight_mode: follow_up` to get a short \"clip for goal N\" message
+# for each highlight instead of the full one.
+#
 # Phone numbers must be stored as \"+15555555\"
 subscriptions:
   - team: 1
     numbers:
       - \"+15555555\"
       - \"+15555678\"
-  - team: 54
+  - team: VGK
+    events: [preview, goals_for, highlights, final]
+    only_if_win: true
+    timezone: America/Los_Angeles
+    quiet_hours:
+      start: 22:00:00
+      end: 08:00:00
     numbers:
       - \"+15557890\"
+  - player: 8478403
+    player_events: goals
+    numbers:
+      - \"+15557890\"
+  - league: true
+    only_final: true
+    numbers:
+      - \"+15555678\"
 ";
 
 #[derive(Deserialize, Debug)]
 pub struct AppConfig {
     pub earliest_notification_time: NaiveTime,
+    #[serde(default)]
+    pub state_dir: PathBuf,
+    #[serde(default)]
+    pub history_db: PathBuf,
+    /// Message text for every subscription, unless a subscription sets its own
+    #[serde(default)]
+    pub templates: Templates,
+    /// Highlight clip renditions to send, most preferred first
+    #[serde(default = "highlight::default_renditions")]
+    pub highlight_renditions: Vec<Rendition>,
+    pub short_links: Option<ShortLinksConfig>,
     pub subscriptions: Vec<TeamSubscription>,
     #[serde(skip)]
     pub twilio: TwilioConfig,
@@ -50,6 +142,22 @@ impl AppConfig {
             &REFERENCE_CONF
         ))?;
 
+        app_config.validate()?;
+
+        if app_config.state_dir.as_os_str().is_empty() {
+            app_config.state_dir = path
+                .parent()
+                .map(|dir| dir.join("state"))
+                .unwrap_or_else(|| PathBuf::from("state"));
+        }
+
+        if app_config.history_db.as_os_str().is_empty() {
+            app_config.history_db = path
+                .parent()
+                .map(|dir| dir.join("history.db"))
+                .unwrap_or_else(|| PathBuf::from("history.db"));
+        }
+
         app_config.twilio.account_sid = twil_sid;
         app_config.twilio.auth_token = twil_token;
         app_config.twilio.from = twil_from;
@@ -57,14 +165,116 @@ impl AppConfig {
         Ok(app_config)
     }
 
-    pub fn subscriptions_as_hashmap(&self) -> HashMap<u32, Vec<String>> {
-        let mut map = HashMap::new();
+    /// Keep state and history in a throwaway dir, so runs that don't really
+    /// send anything never affect a real run. Returns the dir to remove after.
+    pub fn use_scratch_dir(&mut self, name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("nhl-notifier-{}-{}", name, std::process::id()));
+        self.state_dir = dir.join("state");
+        self.history_db = dir.join("history.db");
+        dir
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.templates.validate().context("Invalid template")?;
+
+        for (idx, sub) in self.subscriptions.iter().enumerate() {
+            let targets = [
+                sub.team.is_some(),
+                sub.player.is_some(),
+                sub.league,
+                sub.division.is_some(),
+                sub.conference.is_some(),
+            ];
+            if sub.only_final && sub.events.is_some() {
+                bail!(
+                    "Subscription #{} can't have both only_final and events",
+                    idx + 1
+                );
+            }
+            if targets.iter().filter(|target| **target).count() != 1 {
+                bail!(
+                    "Subscription #{} must have exactly one of team, player, league, division or conference",
+                    idx + 1
+                );
+            }
+            if sub.player.is_some() && (sub.timezone.is_some() || sub.quiet_hours.is_some()) {
+                bail!(
+                    "Subscription #{} follows a player, timezone and quiet_hours are only for team, league, division or conference subscriptions",
+                    idx + 1
+                );
+            }
+            sub.templates.validate().context(format_err!(
+                "Subscription #{} has an invalid template",
+                idx + 1
+            ))?;
+
+            // Subscriptions for a number are merged, they can't disagree on
+            // how it gets messages
+            for number in sub.numbers.iter() {
+                let conflict = self.subscriptions[..idx]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| other.numbers.contains(number))
+                    .find_map(|(other_idx, other)| {
+                        sub.conflicting_setting(other)
+                            .map(|setting| (other_idx, setting))
+                    });
+                if let Some((other_idx, setting)) = conflict {
+                    bail!(
+                        "Subscriptions #{} and #{} give {} different {}, a number can only have one",
+                        other_idx + 1,
+                        idx + 1,
+                        number,
+                        setting
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Subscribers for each team id. League, division and conference
+    /// subscriptions are expanded to their teams using `teams`.
+    pub fn subscriptions_as_hashmap(
+        &self,
+        teams: &[Team],
+    ) -> Result<HashMap<u32, Vec<Subscriber>>, Error> {
+        let mut map: HashMap<u32, Vec<Subscriber>> = HashMap::new();
         for sub in self.subscriptions.iter() {
-            if !sub.numbers.is_empty() {
-                map.insert(sub.team, sub.numbers.clone());
+            if sub.numbers.is_empty() {
+                continue;
+            }
+            for team in sub.team_ids(teams)? {
+                map.entry(team)
+                    .or_default()
+                    .extend(sub.numbers.iter().map(|number| Subscriber {
+                        number: number.clone(),
+                        preferences: vec![Preferences {
+                            teams: if sub.team.is_some() {
+                                vec![team]
+                            } else {
+                                vec![]
+                            },
+                            events: sub.events(),
+                            only_if_win: sub.only_if_win,
+                        }],
+                        timezone: sub.timezone,
+                        quiet_hours: sub.quiet_hours.clone(),
+                        templates: sub.templates.or(&self.templates),
+                        locale: sub.locale,
+                        highlight_mode: sub.highlight_mode,
+                    }));
             }
         }
-        map
+        Ok(map)
+    }
+
+    pub fn player_subscriptions(&self) -> Vec<&TeamSubscription> {
+        self.subscriptions
+            .iter()
+            .filter(|sub| sub.player.is_some() && !sub.numbers.is_empty())
+            .collect()
     }
 }
 
@@ -88,12 +298,494 @@ impl Default for TwilioConfig {
     }
 }
 
+/// Built in short link service for highlight links
+#[derive(Deserialize, Debug)]
+pub struct ShortLinksConfig {
+    /// Public url the redirects are reached at, ie. https://nhl.example.com
+    pub base_url: String,
+    /// Address the redirects are served on
+    pub listen: SocketAddr,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct TeamSubscription {
-    pub team: u32,
+    pub team: Option<TeamRef>,
+    pub player: Option<u32>,
+    #[serde(default)]
+    pub player_events: PlayerEvents,
+    #[serde(default)]
+    pub league: bool,
+    pub division: Option<String>,
+    pub conference: Option<String>,
+    #[serde(default)]
+    pub only_final: bool,
+    pub events: Option<Vec<EventKind>>,
+    #[serde(default)]
+    pub only_if_win: bool,
+    pub timezone: Option<Tz>,
+    pub quiet_hours: Option<QuietHours>,
+    #[serde(default)]
+    pub templates: Templates,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    pub highlight_mode: HighlightMode,
     pub numbers: Vec<String>,
 }
 
+impl TeamSubscription {
+    /// Name of the first setting this and `other` disagree on, for a number
+    /// in both. Player subscriptions have no time zone or quiet hours.
+    fn conflicting_setting(&self, other: &TeamSubscription) -> Option<&'static str> {
+        let both_teams = self.player.is_none() && other.player.is_none();
+        if self.locale != other.locale {
+            Some("locales")
+        } else if self.templates != other.templates {
+            Some("templates")
+        } else if both_teams && self.timezone != other.timezone {
+            Some("timezones")
+        } else if both_teams && self.quiet_hours != other.quiet_hours {
+            Some("quiet_hours")
+        } else {
+            None
+        }
+    }
+
+    fn events(&self) -> Vec<EventKind> {
+        if self.only_final {
+            vec![EventKind::Final]
+        } else if let Some(events) = self.events.as_ref() {
+            events.clone()
+        } else {
+            EventKind::all()
+        }
+    }
+
+    /// Team ids this subscription covers. Player subscriptions cover none, they
+    /// are resolved separately.
+    fn team_ids(&self, teams: &[Team]) -> Result<Vec<u32>, Error> {
+        if let Some(team) = self.team.as_ref() {
+            return Ok(vec![team.resolve(teams)?]);
+        }
+
+        let team_ids: Vec<u32> = if self.league {
+            teams.iter().map(|team| team.id).collect()
+        } else if let Some(division) = self.division.as_ref() {
+            teams
+                .iter()
+                .filter(|team| team.division.name.eq_ignore_ascii_case(division))
+                .map(|team| team.id)
+                .collect()
+        } else if let Some(conference) = self.conference.as_ref() {
+            teams
+                .iter()
+                .filter(|team| team.conference.name.eq_ignore_ascii_case(conference))
+                .map(|team| team.id)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        if team_ids.is_empty() {
+            if let Some(division) = self.division.as_ref() {
+                let mut names: Vec<&str> = teams.iter().map(|t| t.division.name.as_str()).collect();
+                names.sort();
+                names.dedup();
+                bail!(
+                    "No division named {:?}, expected one of: {}",
+                    division,
+                    names.join(", ")
+                );
+            }
+            if let Some(conference) = self.conference.as_ref() {
+                let mut names: Vec<&str> =
+                    teams.iter().map(|t| t.conference.name.as_str()).collect();
+                names.sort();
+                names.dedup();
+                bail!(
+                    "No conference named {:?}, expected one of: {}",
+                    conference,
+                    names.join(", ")
+                );
+            }
+        }
+
+        Ok(team_ids)
+    }
+}
+
+/// A team as written in config, by id, abbreviation or name
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum TeamRef {
+    Id(u32),
+    Name(String),
+}
+
+impl TeamRef {
+    /// Team id this refers to. Abbreviations and names are case insensitive,
+    /// and either the full name (Vegas Golden Knights) or just the team name
+    /// (Golden Knights) can be used.
+    pub fn resolve(&self, teams: &[Team]) -> Result<u32, Error> {
+        let team = match self {
+            TeamRef::Id(id) => teams.iter().find(|team| team.id == *id),
+            TeamRef::Name(name) => {
+                let name = name.trim();
+                teams.iter().find(|team| {
+                    team.abbreviation.eq_ignore_ascii_case(name)
+                        || team.name.eq_ignore_ascii_case(name)
+                        || team.team_name.eq_ignore_ascii_case(name)
+                })
+            }
+        };
+
+        if let Some(team) = team {
+            return Ok(team.id);
+        }
+
+        match self {
+            TeamRef::Id(id) => bail!(
+                "No team with id {}, team id's can be referenced from https://statsapi.web.nhl.com/api/v1/teams",
+                id
+            ),
+            TeamRef::Name(name) => {
+                let suggestions = suggest_teams(name, teams);
+                if suggestions.is_empty() {
+                    bail!(
+                        "No team named {:?}, use a team id, abbreviation or name, ie. 54, VGK or Vegas Golden Knights",
+                        name
+                    );
+                } else {
+                    bail!(
+                        "No team named {:?}, did you mean: {}?",
+                        name,
+                        suggestions.join(", ")
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Teams closest to `name`, to suggest when a team name is misspelled
+fn suggest_teams(name: &str, teams: &[Team]) -> Vec<String> {
+    let name = name.trim().to_lowercase();
+    let max_distance = std::cmp::max(2, name.len() / 3);
+
+    let mut candidates: Vec<(usize, &Team)> = teams
+        .iter()
+        .filter_map(|team| {
+            let distance = [&team.abbreviation, &team.name, &team.team_name]
+                .iter()
+                .map(|candidate| edit_distance(&name, &candidate.to_lowercase()))
+                .min()?;
+            if distance <= max_distance {
+                Some((distance, team))
+            } else {
+                None
+            }
+        })
+        .collect();
+    candidates.sort_by_key(|(distance, _)| *distance);
+
+    candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, team)| format!("{} ({})", team.name, team.abbreviation))
+        .collect()
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            let insertion = current[j] + 1;
+            let deletion = previous[j + 1] + 1;
+            current.push(substitution.min(insertion).min(deletion));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// A number subscribed to a team's games
+#[derive(Debug, Clone)]
+pub struct Subscriber {
+    pub number: String,
+    /// What the number wants from each of its subscriptions, subscriptions
+    /// wanting the same things share one
+    pub preferences: Vec<Preferences>,
+    /// Server's time zone is used if not set
+    pub timezone: Option<Tz>,
+    pub quiet_hours: Option<QuietHours>,
+    pub templates: Templates,
+    pub locale: Locale,
+    pub highlight_mode: HighlightMode,
+}
+
+impl Subscriber {
+    /// Merge subscribers with the same number, so someone subscribed more than
+    /// once to a game (ie. to both teams) only gets each message once
+    pub fn dedup(subscribers: Vec<Subscriber>) -> Vec<Subscriber> {
+        let mut deduped: Vec<Subscriber> = vec![];
+        for sub in subscribers {
+            match deduped.iter_mut().find(|other| other.number == sub.number) {
+                Some(other) => other.merge(sub),
+                None => deduped.push(sub),
+            }
+        }
+        deduped
+    }
+
+    fn merge(&mut self, other: Subscriber) {
+        for preferences in other.preferences {
+            match self
+                .preferences
+                .iter_mut()
+                .find(|own| own.same_as(&preferences))
+            {
+                Some(own) => {
+                    for team in preferences.teams {
+                        if !own.teams.contains(&team) {
+                            own.teams.push(team);
+                        }
+                    }
+                }
+                None => self.preferences.push(preferences),
+            }
+        }
+        // Config validation makes sure a number's subscriptions agree on
+        // these, a player subscription just doesn't have a time zone or quiet
+        // hours
+        self.timezone = self.timezone.or(other.timezone);
+        self.quiet_hours = self.quiet_hours.take().or(other.quiet_hours);
+        self.templates = self.templates.or(&other.templates);
+        if other.highlight_mode == HighlightMode::FollowUp {
+            self.highlight_mode = HighlightMode::FollowUp;
+        }
+    }
+
+    /// Text of a message for this subscriber, from their template or locale
+    pub fn message(&self, kind: MessageKind, values: &[(&str, String)]) -> String {
+        self.templates.render(kind, self.locale, values)
+    }
+
+    pub fn wants(&self, event: EventKind) -> bool {
+        self.preferences
+            .iter()
+            .any(|preferences| preferences.events.contains(&event))
+    }
+
+    pub fn follows(&self, team_id: u32) -> bool {
+        self.preferences
+            .iter()
+            .any(|preferences| preferences.teams.contains(&team_id))
+    }
+
+    /// Teams followed, empty for league, division & conference subscribers
+    pub fn teams(&self) -> Vec<u32> {
+        let mut teams: Vec<u32> = vec![];
+        for team in self.preferences.iter().flat_map(|p| p.teams.iter()) {
+            if !teams.contains(team) {
+                teams.push(*team);
+            }
+        }
+        teams
+    }
+
+    /// Whether a goal scored by `team_id` is wanted, as a goal for or against
+    /// the teams of any of the subscriber's preferences
+    pub fn wants_goal(&self, team_id: u32) -> bool {
+        self.goal_event(team_id).is_some()
+    }
+
+    /// Which of goals for & against a goal scored by `team_id` is wanted as,
+    /// a goal for if any preferences want it that way
+    pub fn goal_event(&self, team_id: u32) -> Option<EventKind> {
+        let events: Vec<EventKind> = self
+            .preferences
+            .iter()
+            .filter_map(|preferences| preferences.goal_event(team_id))
+            .collect();
+        if events.contains(&EventKind::GoalsFor) {
+            Some(EventKind::GoalsFor)
+        } else {
+            events.first().cloned()
+        }
+    }
+
+    /// Whether `event` about a goal scored by `team_id`, such as it being
+    /// overturned, is wanted. Only those told about the goal are told of it.
+    pub fn wants_goal_update(&self, event: EventKind, team_id: u32) -> bool {
+        self.wants(event) && self.wants_goal(team_id)
+    }
+
+    /// `time` in the subscriber's time zone
+    pub fn local_time(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self.timezone {
+            Some(tz) => time.with_timezone(&tz).naive_local(),
+            None => time.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        let time = match self.timezone {
+            Some(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+            None => Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+        };
+        // Local time doesn't exist when clocks go forward, an hour later does
+        time.unwrap_or_else(|| self.to_utc(local + Duration::hours(1)))
+    }
+
+    /// If `now` is in the subscriber's quiet hours, when they end and what to
+    /// do with non-urgent messages until then
+    pub fn quiet_until(&self, now: DateTime<Utc>) -> Option<(DateTime<Utc>, QuietAction)> {
+        let quiet_hours = self.quiet_hours.as_ref()?;
+        let end = quiet_hours.end_after(self.local_time(now))?;
+        Some((self.to_utc(end), quiet_hours.action))
+    }
+
+    pub fn wants_final(&self, winning_team_id: u32) -> bool {
+        self.preferences
+            .iter()
+            .any(|preferences| preferences.wants_final(winning_team_id))
+    }
+}
+
+/// Events a subscriber wants for some teams
+#[derive(Debug, Clone)]
+pub struct Preferences {
+    /// Teams followed, empty for league, division & conference subscriptions
+    pub teams: Vec<u32>,
+    pub events: Vec<EventKind>,
+    /// Only send the final score if a followed team won
+    pub only_if_win: bool,
+}
+
+impl Preferences {
+    /// Whether these want the same things as `other`, for any teams
+    fn same_as(&self, other: &Preferences) -> bool {
+        self.teams.is_empty() == other.teams.is_empty()
+            && self.only_if_win == other.only_if_win
+            && self.events.len() == other.events.len()
+            && self.events.iter().all(|event| other.events.contains(event))
+    }
+
+    /// Whether a goal scored by `team_id` is wanted as a goal for or against
+    /// these teams. Without a team goals are wanted as either.
+    fn goal_event(&self, team_id: u32) -> Option<EventKind> {
+        let event = if self.teams.is_empty() {
+            if self.events.contains(&EventKind::GoalsFor) {
+                EventKind::GoalsFor
+            } else {
+                EventKind::GoalsAgainst
+            }
+        } else if self.teams.contains(&team_id) {
+            EventKind::GoalsFor
+        } else {
+            EventKind::GoalsAgainst
+        };
+        if self.events.contains(&event) {
+            Some(event)
+        } else {
+            None
+        }
+    }
+
+    fn wants_final(&self, winning_team_id: u32) -> bool {
+        if !self.events.contains(&EventKind::Final) {
+            return false;
+        }
+        !self.only_if_win || self.teams.is_empty() || self.teams.contains(&winning_team_id)
+    }
+}
+
+/// Kinds of notification a subscriber can choose to get
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Preview,
+    /// Game has started, with the starting goalies & broadcasts
+    PuckDrop,
+    GoalsFor,
+    GoalsAgainst,
+    Disallowed,
+    ScoringChange,
+    ScoreCorrection,
+    Highlights,
+    Final,
+    Recap,
+    /// Weekly list of upcoming games
+    Digest,
+}
+
+impl EventKind {
+    /// Urgent events are sent during quiet hours
+    pub fn is_urgent(self) -> bool {
+        match self {
+            EventKind::Preview | EventKind::Highlights | EventKind::Recap | EventKind::Digest => {
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Name as written in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            EventKind::Preview => "preview",
+            EventKind::PuckDrop => "puck_drop",
+            EventKind::GoalsFor => "goals_for",
+            EventKind::GoalsAgainst => "goals_against",
+            EventKind::Disallowed => "disallowed",
+            EventKind::ScoringChange => "scoring_change",
+            EventKind::ScoreCorrection => "score_correction",
+            EventKind::Highlights => "highlights",
+            EventKind::Final => "final",
+            EventKind::Recap => "recap",
+            EventKind::Digest => "digest",
+        }
+    }
+
+    pub fn all() -> Vec<EventKind> {
+        vec![
+            EventKind::Preview,
+            EventKind::PuckDrop,
+            EventKind::GoalsFor,
+            EventKind::GoalsAgainst,
+            EventKind::Disallowed,
+            EventKind::ScoringChange,
+            EventKind::ScoreCorrection,
+            EventKind::Highlights,
+            EventKind::Final,
+            EventKind::Recap,
+            EventKind::Digest,
+        ]
+    }
+}
+
+/// Which of a player's points a player subscription is notified for
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayerEvents {
+    Goals,
+    Assists,
+    #[default]
+    Both,
+}
+
 pub fn generate_empty_config() -> Result<(), Error> {
     let mut path = env::current_dir()?;
     path.push("config.yml");
@@ -106,3 +798,267 @@ pub fn generate_empty_config() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    #[serde(default)]
+    pub action: QuietAction,
+}
+
+impl QuietHours {
+    /// If `local` is within quiet hours, when they end. Quiet hours can wrap
+    /// past midnight, ie. 22:00:00 to 08:00:00.
+    fn end_after(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        let time = local.time();
+        let quiet = if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        };
+        if !quiet {
+            return None;
+        }
+
+        if time < self.end {
+            Some(local.date().and_time(self.end))
+        } else {
+            Some((local.date() + Duration::days(1)).and_time(self.end))
+        }
+    }
+}
+
+/// What happens to non-urgent messages during quiet hours
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QuietAction {
+    /// Send once quiet hours end
+    #[default]
+    Hold,
+    Drop,
+}
+
+/// How goal highlights are sent
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightMode {
+    /// A message of its own with the highlight's description
+    #[default]
+    Separate,
+    /// A short message following up the goal by its number
+    FollowUp,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulate::teams_json;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    fn teams() -> Vec<Team> {
+        teams_json()
+            .into_iter()
+            .map(|json| serde_json::from_value(json).unwrap())
+            .collect()
+    }
+
+    fn name(name: &str) -> TeamRef {
+        TeamRef::Name(name.to_owned())
+    }
+
+    fn subscriber(number: &str, teams: Vec<u32>, events: Vec<EventKind>) -> Subscriber {
+        Subscriber {
+            number: number.to_owned(),
+            preferences: vec![Preferences {
+                teams,
+                events,
+                only_if_win: false,
+            }],
+            timezone: None,
+            quiet_hours: None,
+            templates: Templates::default(),
+            locale: Locale::default(),
+            highlight_mode: HighlightMode::default(),
+        }
+    }
+
+    fn subscription(json: serde_json::Value) -> TeamSubscription {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn dedup_merges_preferences_by_number() {
+        let subscribers = Subscriber::dedup(vec![
+            subscriber("+1", vec![54], vec![EventKind::GoalsFor]),
+            subscriber("+2", vec![54], vec![EventKind::GoalsFor]),
+            subscriber("+1", vec![1], vec![EventKind::GoalsFor]),
+            subscriber("+1", vec![], vec![EventKind::Final]),
+        ]);
+
+        assert_eq!(subscribers.len(), 2);
+        let first = &subscribers[0];
+        assert_eq!(first.number, "+1");
+        assert_eq!(first.preferences.len(), 2);
+        assert_eq!(first.preferences[0].teams, vec![54, 1]);
+        assert!(first.preferences[1].teams.is_empty());
+        assert_eq!(first.goal_event(1), Some(EventKind::GoalsFor));
+        assert!(first.wants_final(2));
+        assert_eq!(subscribers[1].teams(), vec![54]);
+    }
+
+    #[test]
+    fn merge_keeps_follow_up_highlights() {
+        let mut sub = subscriber("+1", vec![54], vec![EventKind::Highlights]);
+        let mut other = subscriber("+1", vec![1], vec![EventKind::Highlights]);
+        other.highlight_mode = HighlightMode::FollowUp;
+        sub.merge(other);
+        assert_eq!(sub.highlight_mode, HighlightMode::FollowUp);
+        assert_eq!(sub.teams(), vec![54, 1]);
+    }
+
+    #[test]
+    fn conflicting_subscriptions_for_a_number() {
+        let team = subscription(json!({ "team": "VGK", "numbers": ["+1"] }));
+        let same = subscription(json!({ "team": "NJD", "numbers": ["+1"] }));
+        assert_eq!(team.conflicting_setting(&same), None);
+
+        let french = subscription(json!({ "team": "NJD", "locale": "fr", "numbers": ["+1"] }));
+        assert_eq!(team.conflicting_setting(&french), Some("locales"));
+
+        let timezone = subscription(json!({
+            "team": "NJD",
+            "timezone": "America/New_York",
+            "numbers": ["+1"],
+        }));
+        assert_eq!(team.conflicting_setting(&timezone), Some("timezones"));
+
+        let quiet = subscription(json!({
+            "league": true,
+            "quiet_hours": { "start": "22:00:00", "end": "08:00:00" },
+            "numbers": ["+1"],
+        }));
+        assert_eq!(team.conflicting_setting(&quiet), Some("quiet_hours"));
+
+        let templates = subscription(json!({
+            "team": "NJD",
+            "templates": { "goal": "{scoring_team} scored" },
+            "numbers": ["+1"],
+        }));
+        assert_eq!(team.conflicting_setting(&templates), Some("templates"));
+
+        // Player subscriptions don't have a time zone to disagree on
+        let player = subscription(json!({ "player": 8478403, "numbers": ["+1"] }));
+        assert_eq!(timezone.conflicting_setting(&player), None);
+    }
+
+    fn quiet_hours(start: u32, end: u32) -> QuietHours {
+        QuietHours {
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+            action: QuietAction::Hold,
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2019, 12, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        let quiet = quiet_hours(13, 17);
+        assert_eq!(quiet.end_after(at(2, 12, 59)), None);
+        assert_eq!(quiet.end_after(at(2, 13, 0)), Some(at(2, 17, 0)));
+        assert_eq!(quiet.end_after(at(2, 16, 59)), Some(at(2, 17, 0)));
+        assert_eq!(quiet.end_after(at(2, 17, 0)), None);
+        assert_eq!(quiet.end_after(at(2, 23, 0)), None);
+    }
+
+    #[test]
+    fn quiet_hours_past_midnight() {
+        let quiet = quiet_hours(22, 8);
+        assert_eq!(quiet.end_after(at(2, 21, 59)), None);
+        assert_eq!(quiet.end_after(at(2, 22, 0)), Some(at(3, 8, 0)));
+        assert_eq!(quiet.end_after(at(2, 23, 59)), Some(at(3, 8, 0)));
+        assert_eq!(quiet.end_after(at(3, 0, 0)), Some(at(3, 8, 0)));
+        assert_eq!(quiet.end_after(at(3, 7, 59)), Some(at(3, 8, 0)));
+        assert_eq!(quiet.end_after(at(3, 8, 0)), None);
+        assert_eq!(quiet.end_after(at(3, 12, 0)), None);
+    }
+
+    #[test]
+    fn goal_event_matches_the_goal_side() {
+        let goals_for = subscriber("+1", vec![54], vec![EventKind::GoalsFor]);
+        assert_eq!(goals_for.goal_event(54), Some(EventKind::GoalsFor));
+        assert_eq!(goals_for.goal_event(1), None);
+        assert!(!goals_for.wants_goal_update(EventKind::Disallowed, 54));
+
+        let both = subscriber(
+            "+1",
+            vec![54],
+            vec![
+                EventKind::GoalsFor,
+                EventKind::GoalsAgainst,
+                EventKind::Disallowed,
+            ],
+        );
+        assert_eq!(both.goal_event(1), Some(EventKind::GoalsAgainst));
+        assert!(both.wants_goal_update(EventKind::Disallowed, 1));
+
+        let league = subscriber("+1", vec![], vec![EventKind::GoalsAgainst]);
+        assert_eq!(league.goal_event(54), Some(EventKind::GoalsAgainst));
+    }
+
+    #[test]
+    fn resolve_abbreviation() {
+        assert_eq!(name("VGK").resolve(&teams()).unwrap(), 54);
+    }
+
+    #[test]
+    fn resolve_name() {
+        let teams = teams();
+        assert_eq!(name("Vegas Golden Knights").resolve(&teams).unwrap(), 54);
+        assert_eq!(name("Golden Knights").resolve(&teams).unwrap(), 54);
+        assert_eq!(TeamRef::Id(54).resolve(&teams).unwrap(), 54);
+    }
+
+    #[test]
+    fn resolve_ignores_case() {
+        let teams = teams();
+        assert_eq!(name("vgk").resolve(&teams).unwrap(), 54);
+        assert_eq!(name(" golden KNIGHTS ").resolve(&teams).unwrap(), 54);
+    }
+
+    #[test]
+    fn resolve_typo_suggests_team() {
+        let teams = teams();
+        let error = name("Golden Nights").resolve(&teams).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("did you mean: Vegas Golden Knights (VGK)?"));
+        assert!(TeamRef::Id(9999).resolve(&teams).is_err());
+    }
+
+    #[test]
+    fn suggest_closest_teams() {
+        let teams = teams();
+        assert_eq!(
+            suggest_teams("Devls", &teams),
+            vec!["New Jersey Devils (NJD)"]
+        );
+        assert!(suggest_teams("Toronto Raptors", &teams).is_empty());
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("vgk", "vgk"), 0);
+        assert_eq!(edit_distance("", "vgk"), 3);
+        assert_eq!(edit_distance("devls", "devils"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    config::AppConfig,
+    digest,
+    game::{self, log_words, notification_time_before},
+    log_error,
+    notifier::Notifier,
+    source::StatsSource,
+};
+use async_std::task;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use failure::Error;
+use futures::{
+    future::{self, Either, FutureExt, LocalBoxFuture},
+    stream::{FuturesUnordered, StreamExt},
+    Future,
+};
+use log::info;
+use stats_api::model::ScheduleGame;
+use std::{collections::HashSet, rc::Rc};
+
+/// How long a game may have been going for and still be picked up, ie. after
+/// a restart mid-game
+const IN_PROGRESS_WINDOW_HOURS: i64 = 6;
+
+/// How long to wait before trying again after a failure
+const RETRY_INTERVAL_SECS: u64 = 60 * 5;
+
+/// Games and digests being run. A game that couldn't be set up gives its id,
+/// after waiting to try it again.
+type Running<'a> = FuturesUnordered<LocalBoxFuture<'a, Option<u64>>>;
+
+/// Run forever, handling every game day. Wakes each day at
+/// `earliest_notification_time` and starts the games starting before the next
+/// day's notification time, including any that start after midnight. Games
+/// and the digest keep running while the daemon waits for the next day.
+pub async fn run_daemon(
+    config: &AppConfig,
+    source: Rc<dyn StatsSource>,
+    notifier: Rc<dyn Notifier>,
+) -> Result<(), Error> {
+    // Subscriptions are resolved up front, so a misspelled team stops the
+    // daemon straight away instead of on the first game day
+    let teams = loop {
+        match source.get_teams().await {
+            Ok(teams) => break teams,
+            Err(e) => {
+                log_error(&e);
+                retry_sleep().await;
+            }
+        }
+    };
+    let subscriptions = config.subscriptions_as_hashmap(&teams)?;
+
+    let mut games_run: HashSet<u64> = HashSet::new();
+    let mut running: Running = FuturesUnordered::new();
+    let mut digest_started: Option<NaiveDate> = None;
+
+    loop {
+        let now = Local::now();
+        let next_wake =
+            notification_time_before(now, config.earliest_notification_time) + Duration::days(1);
+
+        let games = match upcoming_games(source.as_ref(), now, next_wake).await {
+            Ok(games) => games,
+            Err(e) => {
+                log_error(&e);
+                wait_running(retry_sleep(), &mut running, &mut games_run).await;
+                continue;
+            }
+        };
+
+        let games: Vec<ScheduleGame> = games
+            .into_iter()
+            .filter(|game| !games_run.contains(&game.game_pk))
+            .collect();
+
+        // The digest goes out once on digest day, after the notification time.
+        // Any that don't go out are tried again until the day is over.
+        let today = now.naive_local().date();
+        if digest::is_digest_due(now, config.earliest_notification_time)
+            && digest_started != Some(today)
+        {
+            digest_started = Some(today);
+            let source = source.clone();
+            let notifier = notifier.clone();
+            running.push(
+                async move {
+                    while !digest::send_weekly_digest(config, source.as_ref(), notifier.as_ref())
+                        .await
+                    {
+                        retry_sleep().await;
+                        if Local::now().naive_local().date() != today {
+                            break;
+                        }
+                    }
+                    None
+                }
+                .boxed_local(),
+            );
+        }
+
+        if !games.is_empty() {
+            let (verb, plural) = log_words(&games);
+            info!(
+                "There {} {} game{} before {}",
+                verb,
+                games.len(),
+                plural,
+                next_wake.to_rfc2822()
+            );
+
+            // Games without a subscription are marked run too, any that
+            // couldn't be set up are unmarked to be started again
+            games_run.extend(games.iter().map(|game| game.game_pk));
+            let runs = game::game_runs(
+                config,
+                source.clone(),
+                notifier.clone(),
+                &subscriptions,
+                games,
+            )
+            .await;
+            for run in runs {
+                running.push(
+                    async move {
+                        match run.await {
+                            (_, true) => None,
+                            (game_id, false) => {
+                                retry_sleep().await;
+                                Some(game_id)
+                            }
+                        }
+                    }
+                    .boxed_local(),
+                );
+            }
+        }
+
+        if running.is_empty() {
+            info!(
+                "No more games today, sleeping until {}...",
+                next_wake.to_rfc2822()
+            );
+        }
+        wait_running(sleep_until(next_wake), &mut running, &mut games_run).await;
+    }
+}
+
+/// Run games until `wait` is done, or until a game that couldn't be set up is
+/// ready to try again. That game is unmarked so it's started again.
+async fn wait_running<'a, F>(wait: F, running: &mut Running<'a>, games_run: &mut HashSet<u64>)
+where
+    F: Future<Output = ()>,
+{
+    let mut wait = Box::pin(wait);
+    loop {
+        if running.is_empty() {
+            return wait.await;
+        }
+
+        match future::select(wait, running.next()).await {
+            Either::Left(_) => return,
+            Either::Right((Some(Some(game_id)), _)) => {
+                games_run.remove(&game_id);
+                return;
+            }
+            Either::Right((_, rest)) => wait = rest,
+        }
+    }
+}
+
+/// Games starting from `IN_PROGRESS_WINDOW_HOURS` before `from` up to `until`
+async fn upcoming_games(
+    source: &dyn StatsSource,
+    from: DateTime<Local>,
+    until: DateTime<Local>,
+) -> Result<Vec<ScheduleGame>, Error> {
+    let start = from - Duration::hours(IN_PROGRESS_WINDOW_HOURS);
+    game::games_between(source, start, until).await
+}
+
+async fn retry_sleep() {
+    info!("Retrying in {} minutes...", RETRY_INTERVAL_SECS / 60);
+    task::sleep(std::time::Duration::from_secs(RETRY_INTERVAL_SECS)).await;
+}
+
+async fn sleep_until(time: DateTime<Local>) {
+    if let Ok(duration) = (time - Local::now()).to_std() {
+        task::sleep(duration).await;
+    }
+}
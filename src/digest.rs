@@ -0,0 +1,284 @@
+use crate::{
+    config::{AppConfig, EventKind, QuietAction, Subscriber},
+    game::{games_between, notification_time_before},
+    history::{History, HistoryFilter, Notification},
+    locale::Locale,
+    log_error,
+    notifier::Notifier,
+    source::StatsSource,
+};
+use async_std::task;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Utc, Weekday};
+use failure::Error;
+use log::{info, warn};
+use stats_api::model::{ScheduleGame, Team};
+
+/// Digests go out on the first run of this day
+const DIGEST_DAY: Weekday = Weekday::Mon;
+
+/// How far ahead a digest lists games
+const DIGEST_DAYS: i64 = 7;
+
+/// Recorded as the game id of digests in notification history, since they
+/// aren't for a single game
+const DIGEST_GAME_ID: u64 = 0;
+
+/// Send each subscriber following a team a list of their teams' games over
+/// the next week. Subscribers already sent a digest today are skipped, and
+/// digests for subscribers in quiet hours are held or dropped like any other
+/// non-urgent message. Errors are logged rather than returned, so a failed
+/// digest never stops games being notified. Returns whether every digest
+/// went out, if not it can be run again for those that didn't.
+pub async fn send_weekly_digest(
+    config: &AppConfig,
+    source: &dyn StatsSource,
+    notifier: &dyn Notifier,
+) -> bool {
+    match try_send_weekly_digest(config, source, notifier).await {
+        Ok(all_sent) => all_sent,
+        Err(e) => {
+            log_error(&e.context("Could not send weekly digest").into());
+            false
+        }
+    }
+}
+
+async fn try_send_weekly_digest(
+    config: &AppConfig,
+    source: &dyn StatsSource,
+    notifier: &dyn Notifier,
+) -> Result<bool, Error> {
+    let history = match History::open(&config.history_db) {
+        Ok(history) => Some(history),
+        Err(e) => {
+            warn!("Digest history won't be recorded: {}", e);
+            None
+        }
+    };
+
+    let teams = source.get_teams().await?;
+    let subscriptions = config.subscriptions_as_hashmap(&teams)?;
+    let subscribers = Subscriber::dedup(subscriptions.values().flatten().cloned().collect());
+
+    let now = Local::now();
+    let games = games_between(source, now, now + Duration::days(DIGEST_DAYS)).await?;
+
+    let mut held: Vec<(DateTime<Utc>, Subscriber, String)> = vec![];
+    let mut all_sent = true;
+    for sub in subscribers {
+        if !sub.wants(EventKind::Digest) || sub.teams().is_empty() {
+            continue;
+        }
+        if already_sent(history.as_ref(), &sub.number) {
+            info!("Digest already sent today for: {}", sub.number);
+            continue;
+        }
+
+        let message = match digest_message(&sub, &games, &teams) {
+            Some(message) => message,
+            None => continue,
+        };
+
+        if let Some((until, action)) = sub.quiet_until(Utc::now()) {
+            match action {
+                QuietAction::Hold => {
+                    info!(
+                        "Quiet hours, holding digest for: {} until {}",
+                        sub.number,
+                        until.with_timezone(&Local).to_rfc2822()
+                    );
+                    held.push((until, sub, message));
+                }
+                QuietAction::Drop => {
+                    info!("Quiet hours, dropping digest for: {}", sub.number);
+                    record(history.as_ref(), notifier, &sub.number, None, "dropped");
+                }
+            }
+            continue;
+        }
+
+        all_sent &= send(history.as_ref(), notifier, &sub.number, &message).await;
+    }
+
+    held.sort_by_key(|(until, _, _)| *until);
+    for (until, sub, message) in held {
+        if let Ok(duration) = (until - Utc::now()).to_std() {
+            task::sleep(duration).await;
+        }
+        all_sent &= send(history.as_ref(), notifier, &sub.number, &message).await;
+    }
+
+    Ok(all_sent)
+}
+
+/// One line per game involving the subscriber's teams, with the start time
+/// in their time zone. None if their teams don't play this week.
+fn digest_message(sub: &Subscriber, games: &[ScheduleGame], teams: &[Team]) -> Option<String> {
+    let lines: Vec<String> = games
+        .iter()
+        .filter_map(|game| {
+            let home_id = game.teams.home.detail.id;
+            let away_id = game.teams.away.detail.id;
+
+            let (team_id, opponent_id, is_home) = if sub.follows(home_id) {
+                (home_id, away_id, true)
+            } else if sub.follows(away_id) {
+                (away_id, home_id, false)
+            } else {
+                return None;
+            };
+            let (versus, place) = match (sub.locale, is_home) {
+                (Locale::En, true) => ("vs", "home"),
+                (Locale::En, false) => ("@", "away"),
+                (Locale::Fr, true) => ("c.", "domicile"),
+                (Locale::Fr, false) => ("à", "visiteur"),
+            };
+
+            Some(format!(
+                "{} - {} {} {} ({})",
+                sub.locale.game_day(sub.local_time(game.date)),
+                abbreviation(teams, team_id),
+                versus,
+                abbreviation(teams, opponent_id),
+                place,
+            ))
+        })
+        .collect();
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    let title = match sub.locale {
+        Locale::En => "This week's games:",
+        Locale::Fr => "Matchs de la semaine :",
+    };
+    Some(format!("{}\n\n{}", title, lines.join("\n")))
+}
+
+fn abbreviation(teams: &[Team], team_id: u32) -> String {
+    teams
+        .iter()
+        .find(|team| team.id == team_id)
+        .map(|team| team.abbreviation.clone())
+        .unwrap_or_else(|| team_id.to_string())
+}
+
+fn already_sent(history: Option<&History>, number: &str) -> bool {
+    let history = match history {
+        Some(history) => history,
+        None => return false,
+    };
+
+    let filter = HistoryFilter {
+        date: Some(Local::now().naive_local().date()),
+        game_id: Some(DIGEST_GAME_ID),
+        number: Some(number.to_owned()),
+    };
+    match history.query(&filter) {
+        Ok(notifications) => notifications.iter().any(|notification| {
+            notification.event == EventKind::Digest.name() && notification.delivered()
+        }),
+        Err(e) => {
+            warn!("Could not check digest history: {}", e);
+            false
+        }
+    }
+}
+
+/// Send one digest, returning whether it was delivered
+async fn send(
+    history: Option<&History>,
+    notifier: &dyn Notifier,
+    number: &str,
+    message: &str,
+) -> bool {
+    match notifier.send(number, message).await {
+        Ok(delivery) => {
+            if delivery.delivered {
+                info!("Digest sent for: {}", number);
+            } else {
+                warn!("Digest couldn't send for: {}", number);
+            }
+            record(history, notifier, number, delivery.sid, &delivery.status);
+            delivery.delivered
+        }
+        Err(e) => {
+            warn!("Failure sending digest, error: {:?}", e);
+            record(history, notifier, number, None, "failed");
+            false
+        }
+    }
+}
+
+fn record(
+    history: Option<&History>,
+    notifier: &dyn Notifier,
+    number: &str,
+    sid: Option<String>,
+    status: &str,
+) {
+    if let Some(history) = history {
+        let notification = Notification {
+            game_id: DIGEST_GAME_ID,
+            event: EventKind::Digest.name().to_owned(),
+            recipient: number.to_owned(),
+            channel: notifier.channel().to_owned(),
+            sid,
+            status: status.to_owned(),
+            sent_at: Utc::now(),
+        };
+
+        if let Err(e) = history.record(&notification) {
+            warn!("Could not record digest history: {}", e);
+        }
+    }
+}
+
+/// Whether `now` is on digest day, after that day's notification time
+pub fn is_digest_due(now: DateTime<Local>, earliest_notification_time: NaiveTime) -> bool {
+    until_digest_due(now, earliest_notification_time) == Some(Duration::zero())
+}
+
+/// How long until the digest is due if `now` is on digest day, zero once that
+/// day's notification time has passed
+pub fn until_digest_due(
+    now: DateTime<Local>,
+    earliest_notification_time: NaiveTime,
+) -> Option<Duration> {
+    if now.weekday() != DIGEST_DAY {
+        return None;
+    }
+
+    let notification_time = notification_time_before(now, earliest_notification_time);
+    if notification_time.naive_local().date() == now.naive_local().date() {
+        Some(Duration::zero())
+    } else {
+        Some(notification_time + Duration::days(1) - now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        // 2019-12-02 was a Monday
+        let date = NaiveDate::from_ymd_opt(2019, 12, day).unwrap();
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn digest_waits_for_notification_time() {
+        let ten = NaiveTime::from_hms_opt(10, 0, 0).unwrap();
+        assert_eq!(until_digest_due(at(2, 7), ten), Some(Duration::hours(3)));
+        assert_eq!(until_digest_due(at(2, 10), ten), Some(Duration::zero()));
+        assert_eq!(until_digest_due(at(2, 23), ten), Some(Duration::zero()));
+        assert_eq!(until_digest_due(at(3, 11), ten), None);
+        assert!(!is_digest_due(at(2, 7), ten));
+        assert!(is_digest_due(at(2, 11), ten));
+    }
+}
@@ -1,20 +1,62 @@
-use crate::config::AppConfig;
+use crate::{
+    config::{
+        AppConfig, EventKind, HighlightMode, PlayerEvents, Preferences, QuietAction, Subscriber,
+    },
+    digest,
+    highlight::{choose_playback, Rendition},
+    history::{History, Notification},
+    locale::Locale,
+    notifier::Notifier,
+    shortlink::{LinkStore, Shortener},
+    source::StatsSource,
+    state::StateStore,
+    template::{MessageKind, Templates},
+};
 use async_std::task;
-use chrono::{DateTime, Local, NaiveTime, Utc};
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
 use failure::{bail, format_err, Error};
-use futures::future;
+use feed::model::{BoxscoreTeam, Broadcast, LiveFeed};
+use futures::future::{self, FutureExt, LocalBoxFuture};
 use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
 use stats_api::model::{
     GameContentEditorialItemArticle, GameContentMilestoneItem, GameContentMilestoneItemHighlight,
     GameContentMilestones, ScheduleGame, Team,
 };
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// How often the live linescore is checked against the milestone score
+const LINESCORE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long the milestone score can disagree with the linescore before it's
+/// corrected. Milestones often trail the linescore by a few minutes.
+const SCORE_MISMATCH_GRACE: Duration = Duration::from_secs(60 * 5);
 
-pub async fn run_todays_games(config: &AppConfig) -> Result<(), Error> {
-    let client = stats_api::Client::new();
+/// How many polls in a row a goal can be missing from the milestones before
+/// it's taken as overturned, when the linescore doesn't show it first
+const OVERTURN_POLLS: u32 = 3;
 
+/// How often to check for the recap article and three stars after the game
+const RECAP_INTERVAL: Duration = Duration::from_secs(60 * 5);
+
+/// How many checks before the recap is sent with whatever is available
+const RECAP_ATTEMPTS: u32 = 24;
+
+/// How long saved game state is kept for
+const STATE_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+pub async fn run_todays_games(
+    config: &AppConfig,
+    source: Rc<dyn StatsSource>,
+    notifier: Rc<dyn Notifier>,
+) -> Result<(), Error> {
     let today = Local::today().naive_local();
-    let todays_schedule = client.get_schedule_for(today).await?;
+    let todays_schedule = source.get_schedule_for(today).await?;
 
     let (verb, plural) = log_words(&todays_schedule.games);
     info!(
@@ -25,7 +67,92 @@ pub async fn run_todays_games(config: &AppConfig) -> Result<(), Error> {
         todays_schedule.date.format("%A, %-d %B, %C%y").to_string(),
     );
 
-    let subscriptions = config.subscriptions_as_hashmap();
+    let teams = source.get_teams().await?;
+    let subscriptions = config.subscriptions_as_hashmap(&teams)?;
+
+    // Games are run alongside the digest, which is held until the
+    // notification time and may be held again for quiet hours
+    let digest = async {
+        let until_due = digest::until_digest_due(Local::now(), config.earliest_notification_time);
+        if let Some(until_due) = until_due {
+            if let Ok(wait) = until_due.to_std() {
+                if wait > Duration::from_secs(0) {
+                    info!(
+                        "Weekly digest will be sent in {} minutes",
+                        until_due.num_minutes()
+                    );
+                    task::sleep(wait).await;
+                }
+            }
+            digest::send_weekly_digest(config, source.as_ref(), notifier.as_ref()).await;
+        }
+    };
+    future::join(
+        digest,
+        run_games(
+            config,
+            source.clone(),
+            notifier.clone(),
+            &subscriptions,
+            todays_schedule.games,
+        ),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Run every game that has a subscription until it's over. Returns the ids of
+/// the games that ran, any that couldn't be set up are left out.
+pub async fn run_games(
+    config: &AppConfig,
+    source: Rc<dyn StatsSource>,
+    notifier: Rc<dyn Notifier>,
+    subscriptions: &HashMap<u32, Vec<Subscriber>>,
+    games: Vec<ScheduleGame>,
+) -> Vec<u64> {
+    let runs = game_runs(config, source, notifier, subscriptions, games).await;
+    future::join_all(runs)
+        .await
+        .into_iter()
+        .filter_map(|(game_id, ran)| if ran { Some(game_id) } else { None })
+        .collect()
+}
+
+/// A future for each game that has a subscription, running it until it's
+/// over. Each gives the game's id and whether it ran, a game that couldn't be
+/// set up can be tried again.
+pub async fn game_runs<'a>(
+    config: &'a AppConfig,
+    source: Rc<dyn StatsSource>,
+    notifier: Rc<dyn Notifier>,
+    subscriptions: &HashMap<u32, Vec<Subscriber>>,
+    games: Vec<ScheduleGame>,
+) -> Vec<LocalBoxFuture<'a, (u64, bool)>> {
+    if let Err(e) = StateStore::new(&config.state_dir).prune(STATE_MAX_AGE) {
+        warn!("Could not prune old game state: {}", e);
+    }
+
+    // Notifications still go out if the history database is unavailable
+    let history = match History::open(&config.history_db) {
+        Ok(history) => Some(Arc::new(history)),
+        Err(e) => {
+            warn!("Notification history won't be recorded: {}", e);
+            None
+        }
+    };
+
+    let shortener = match config.short_links.as_ref() {
+        Some(short_links) => match LinkStore::open(&config.history_db, &short_links.base_url) {
+            Ok(store) => Some(Rc::new(store) as Rc<dyn Shortener>),
+            Err(e) => {
+                warn!("Highlight links won't be shortened: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
     let subscription_team_ids: Vec<u32> = subscriptions.keys().cloned().collect();
 
     let (verb, plural) = log_words(&subscription_team_ids);
@@ -36,8 +163,17 @@ pub async fn run_todays_games(config: &AppConfig) -> Result<(), Error> {
         plural,
     );
 
-    let games_to_notify: Vec<(ScheduleGame, Vec<String>)> = todays_schedule
-        .games
+    let player_subscriptions = resolve_player_subscriptions(config, source.as_ref()).await;
+
+    let (verb, plural) = log_words(&player_subscriptions);
+    info!(
+        "There {} {} player{} with an active subscription",
+        verb,
+        player_subscriptions.len(),
+        plural,
+    );
+
+    let games_to_notify: Vec<(ScheduleGame, Vec<Subscriber>, Vec<PlayerSubscription>)> = games
         .into_iter()
         .filter_map(|game| {
             let mut numbers = vec![];
@@ -57,10 +193,18 @@ pub async fn run_todays_games(config: &AppConfig) -> Result<(), Error> {
                         .clone(),
                 );
             }
-            if numbers.is_empty() {
+            let players: Vec<PlayerSubscription> = player_subscriptions
+                .iter()
+                .filter(|player| {
+                    player.team_id == game.teams.home.detail.id
+                        || player.team_id == game.teams.away.detail.id
+                })
+                .cloned()
+                .collect();
+            if numbers.is_empty() && players.is_empty() {
                 None
             } else {
-                Some((game, numbers))
+                Some((game, Subscriber::dedup(numbers), players))
             }
         })
         .collect();
@@ -73,93 +217,252 @@ pub async fn run_todays_games(config: &AppConfig) -> Result<(), Error> {
         plural,
     );
 
-    let run_games = games_to_notify
+    let context = GameContext {
+        source,
+        notifier,
+        history,
+        shortener,
+    };
+
+    games_to_notify
         .into_iter()
         .map(|_game| {
-            async {
+            let context = context.clone();
+            async move {
+                let game_id = _game.0.game_pk;
                 let game_name = format!(
                     "{} vs. {}",
                     _game.0.teams.home.detail.name, _game.0.teams.away.detail.name
                 );
-                let game = Game::new(_game.0, _game.1, &config).await;
+                let game = Game::new(_game.0, _game.1, _game.2, context, config).await;
                 match game {
                     Ok(mut game) => {
                         game.run().await;
+                        (game_id, true)
+                    }
+                    Err(e) => {
+                        error!("Error running game for {}: {:?}", game_name, e);
+                        (game_id, false)
                     }
-                    Err(e) => error!("Error running game for {}: {:?}", game_name, e),
                 }
             }
+            .boxed_local()
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
+
+/// Games starting from `start` up to `until`, in order. The schedule is dated
+/// in the league's time zone, so the days either side of the local dates are
+/// fetched as well.
+pub async fn games_between(
+    source: &dyn StatsSource,
+    start: DateTime<Local>,
+    until: DateTime<Local>,
+) -> Result<Vec<ScheduleGame>, Error> {
+    let mut games: Vec<ScheduleGame> = vec![];
+    let mut date = start.naive_local().date() - chrono::Duration::days(1);
+    while date <= until.naive_local().date() {
+        let schedule = source.get_schedule_for(date).await?;
+        for game in schedule.games {
+            let game_start = game.date.with_timezone(&Local);
+            let in_range = game_start >= start && game_start < until;
+            if in_range && !games.iter().any(|other| other.game_pk == game.game_pk) {
+                games.push(game);
+            }
+        }
+        date += chrono::Duration::days(1);
+    }
+    games.sort_by_key(|game| game.date);
 
-    future::join_all(run_games).await;
+    Ok(games)
+}
 
-    Ok(())
+/// Look up the current team of each subscribed player, so we know which
+/// games they're in
+pub async fn resolve_player_subscriptions(
+    config: &AppConfig,
+    source: &dyn StatsSource,
+) -> Vec<PlayerSubscription> {
+    let mut player_subscriptions = vec![];
+    for sub in config.player_subscriptions() {
+        let player_id = match sub.player {
+            Some(player_id) => player_id,
+            None => continue,
+        };
+
+        match source.get_person(player_id).await {
+            Ok(person) => {
+                if let Some(team) = person.current_team {
+                    player_subscriptions.push(PlayerSubscription {
+                        player_id,
+                        name: person.full_name,
+                        team_id: team.id,
+                        events: sub.player_events,
+                        numbers: sub.numbers.clone(),
+                        templates: sub.templates.or(&config.templates),
+                        locale: sub.locale,
+                    });
+                } else {
+                    warn!(
+                        "Player({}) - {} isn't on a team, skipping subscription",
+                        player_id, person.full_name
+                    );
+                }
+            }
+            Err(e) => error!("Error looking up Player({}): {:?}", player_id, e),
+        }
+    }
+    player_subscriptions
+}
+
+/// What every game running at the same time shares
+#[derive(Clone)]
+struct GameContext {
+    source: Rc<dyn StatsSource>,
+    notifier: Rc<dyn Notifier>,
+    history: Option<Arc<History>>,
+    shortener: Option<Rc<dyn Shortener>>,
 }
 
 struct Game {
-    stats_client: stats_api::Client,
-    twil_client: twilio::Client,
-    twil_from: String,
-    earliest_notification: chrono::NaiveTime,
+    source: Rc<dyn StatsSource>,
+    notifier: Rc<dyn Notifier>,
+    notify_after: DateTime<Local>,
     game_id: u64,
     game_type: String,
     date: DateTime<Utc>,
     home_team: Team,
     away_team: Team,
     score: GameScore,
+    linescore_checked: Option<DateTime<Utc>>,
+    score_mismatch_since: Option<DateTime<Utc>>,
     goals: HashMap<u32, Goal>,
+    /// Polls in a row each notified goal has been missing from the milestones
+    missing_goals: HashMap<u32, u32>,
     highlights_notified: Vec<u32>,
-    subscriptions: Vec<String>,
+    highlight_renditions: Vec<Rendition>,
+    subscriptions: Vec<Subscriber>,
+    player_subscriptions: Vec<PlayerSubscription>,
+    held_messages: Mutex<Vec<HeldMessage>>,
     preview: Option<GameContentEditorialItemArticle>,
+    preview_sent: bool,
+    puck_drop_sent: bool,
     status: GameStatus,
+    state_store: StateStore,
+    history: Option<Arc<History>>,
+    shortener: Option<Rc<dyn Shortener>>,
 }
 
 impl Game {
     async fn new(
         game: ScheduleGame,
-        subscriptions: Vec<String>,
+        subscriptions: Vec<Subscriber>,
+        player_subscriptions: Vec<PlayerSubscription>,
+        context: GameContext,
         config: &AppConfig,
     ) -> Result<Self, Error> {
-        let stats_client = stats_api::Client::new();
-
-        let twil_client = twilio::Client::new(
-            config.twilio.account_sid.clone(),
-            config.twilio.auth_token.clone(),
-        );
-        let twil_from = config.twilio.from.clone();
-
-        let earliest_notification = config.earliest_notification_time;
-
+        let GameContext {
+            source,
+            notifier,
+            history,
+            shortener,
+        } = context;
         let game_id = game.game_pk;
         let date = game.date;
 
-        let home_team = stats_client.get_team(game.teams.home.detail.id).await?;
-        let away_team = stats_client.get_team(game.teams.away.detail.id).await?;
+        // Games that start after midnight are notified from the day before
+        let notify_after = notification_time_before(
+            date.with_timezone(&Local),
+            config.earliest_notification_time,
+        );
+
+        let home_team = source.get_team(game.teams.home.detail.id).await?;
+        let away_team = source.get_team(game.teams.away.detail.id).await?;
 
-        Ok(Game {
-            stats_client,
-            twil_client,
-            twil_from,
-            earliest_notification,
+        let mut game = Game {
+            source,
+            notifier,
+            notify_after,
             game_id,
             game_type: game.game_type,
             date,
             home_team,
             away_team,
             score: GameScore::new(),
+            linescore_checked: None,
+            score_mismatch_since: None,
             goals: HashMap::new(),
+            missing_goals: HashMap::new(),
             highlights_notified: vec![],
+            highlight_renditions: config.highlight_renditions.clone(),
             subscriptions,
+            player_subscriptions,
+            held_messages: Mutex::new(vec![]),
             preview: None,
+            preview_sent: false,
+            puck_drop_sent: false,
             status: GameStatus::Scheduled,
-        })
+            state_store: StateStore::new(&config.state_dir),
+            history,
+            shortener,
+        };
+        game.load_state();
+
+        Ok(game)
+    }
+
+    /// Pick up where we left off if this game was already running before a
+    /// restart, so nothing is notified twice
+    fn load_state(&mut self) {
+        match self.state_store.load::<GameState>(self.game_id) {
+            Ok(Some(state)) => {
+                self.log_info(format!(
+                    "Resuming from saved state, {} goal{} already notified",
+                    state.goals.len(),
+                    if state.goals.len() == 1 { "" } else { "s" }
+                ));
+                self.status = state.status;
+                self.preview_sent = state.preview_sent;
+                self.puck_drop_sent = state.puck_drop_sent;
+                self.score = state.score;
+                self.goals = state.goals;
+                self.highlights_notified = state.highlights_notified;
+                *self.held_messages.lock().unwrap() = state.held_messages;
+            }
+            Ok(None) => {}
+            Err(e) => self.log_error(format_err!("Could not load saved state: {:?}", e)),
+        }
+    }
+
+    fn save_state(&self) {
+        let state = GameState {
+            status: self.status,
+            preview_sent: self.preview_sent,
+            puck_drop_sent: self.puck_drop_sent,
+            score: self.score.clone(),
+            goals: self.goals.clone(),
+            highlights_notified: self.highlights_notified.clone(),
+            held_messages: self.held_messages.lock().unwrap().clone(),
+        };
+
+        if let Err(e) = self.state_store.save(self.game_id, &state) {
+            self.log_error(format_err!("Could not save state: {:?}", e));
+        }
     }
 
     fn local_datetime(&self) -> DateTime<Local> {
         self.date.with_timezone(&Local)
     }
 
+    /// Game time passed since `time`, by the source's clock
+    fn since(&self, time: DateTime<Utc>) -> Duration {
+        (self.source.now() - time).to_std().unwrap_or_default()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        self.source.sleep(duration).await;
+    }
+
     fn log_info<S: std::fmt::Display>(&self, msg: S) {
         info!("Game({}) - {}", self.game_id, msg);
     }
@@ -173,7 +476,7 @@ impl Game {
     }
 
     async fn get_milestones(&mut self) -> Result<GameContentMilestones, Error> {
-        let content = self.stats_client.get_game_content(self.game_id).await?;
+        let content = self.source.get_game_content(self.game_id).await?;
         let milestones = content.media.milestones;
         Ok(milestones)
     }
@@ -189,7 +492,7 @@ impl Game {
     }
 
     async fn get_preview(&mut self) -> Result<(), Error> {
-        let content = self.stats_client.get_game_content(self.game_id).await?;
+        let content = self.source.get_game_content(self.game_id).await?;
         let preview_items = content.editorial.preview.items;
         if let Some(items) = preview_items {
             if let Some(preview) = items.first() {
@@ -200,6 +503,17 @@ impl Game {
         bail!("Preview not available");
     }
 
+    async fn get_recap(&self) -> Result<GameContentEditorialItemArticle, Error> {
+        let content = self.source.get_game_content(self.game_id).await?;
+        let recap_items = content.editorial.recap.items;
+        if let Some(items) = recap_items {
+            if let Some(recap) = items.first() {
+                return Ok(recap.clone());
+            }
+        }
+        bail!("Recap not available");
+    }
+
     fn subhead(&self) -> String {
         if let Some(preview) = self.preview.as_ref() {
             return preview.subhead.clone();
@@ -208,38 +522,169 @@ impl Game {
         String::from("")
     }
 
-    async fn send_message(&self, message: &str) {
-        for number in self.subscriptions.iter() {
-            let _response = self
-                .twil_client
-                .send_message(self.twil_from.as_str(), number, message)
-                .await;
+    /// Send every subscriber that wants this kind of event the message
+    /// `message_for` their locale returns
+    async fn send_localized<F>(&self, event: EventKind, message_for: F)
+    where
+        F: Fn(Locale) -> String,
+    {
+        self.fan_out(event, |sub| {
+            if sub.wants(event) {
+                Some(message_for(sub.locale))
+            } else {
+                None
+            }
+        })
+        .await;
+    }
 
-            match _response {
-                Ok(response) => {
-                    if response.status == "sent" || response.status == "queued" {
-                        self.log_info(format!("Notification sent for: {}", number));
-                    } else {
-                        self.log_error(format!("Notification couldn't send for: {}", number));
+    /// Send every subscriber that wants this kind of event their template for
+    /// the message, filled in with `values_for` their locale
+    async fn send_templated<F>(&self, event: EventKind, kind: MessageKind, values_for: F)
+    where
+        F: Fn(Locale) -> Vec<(&'static str, String)>,
+    {
+        self.fan_out(event, |sub| {
+            if sub.wants(event) {
+                Some(sub.message(kind, &values_for(sub.locale)))
+            } else {
+                None
+            }
+        })
+        .await;
+    }
+
+    /// Send each subscriber the message `message_for` returns for them, if any.
+    /// Non-urgent events are held or dropped during a subscriber's quiet hours.
+    async fn fan_out<F>(&self, event: EventKind, message_for: F)
+    where
+        F: Fn(&Subscriber) -> Option<String>,
+    {
+        let now = self.source.now();
+        for sub in self.subscriptions.iter() {
+            let message = match message_for(sub) {
+                Some(message) => message,
+                None => continue,
+            };
+
+            if !event.is_urgent() {
+                if let Some((until, action)) = sub.quiet_until(now) {
+                    match action {
+                        QuietAction::Hold => {
+                            self.log_info(format!(
+                                "Quiet hours, holding {:?} notification for: {} until {}",
+                                event,
+                                sub.number,
+                                until.with_timezone(&Local).to_rfc2822()
+                            ));
+                            self.held_messages.lock().unwrap().push(HeldMessage {
+                                event: event.name().to_owned(),
+                                number: sub.number.clone(),
+                                message,
+                                until,
+                            });
+                            self.save_state();
+                        }
+                        QuietAction::Drop => {
+                            self.log_info(format!(
+                                "Quiet hours, dropping {:?} notification for: {}",
+                                event, sub.number
+                            ));
+                            self.record_history(event.name(), &sub.number, None, "dropped");
+                        }
                     }
+                    continue;
+                }
+            }
+
+            self.deliver(event.name(), &sub.number, &message).await;
+        }
+    }
+
+    /// Send any held messages whose quiet hours have ended
+    async fn send_held_messages(&self) {
+        let now = self.source.now();
+        let due: Vec<HeldMessage> = {
+            let mut held_messages = self.held_messages.lock().unwrap();
+            let (due, held): (Vec<_>, Vec<_>) =
+                held_messages.drain(..).partition(|held| held.until <= now);
+            *held_messages = held;
+            due
+        };
+        if !due.is_empty() {
+            self.save_state();
+        }
+
+        for held in due {
+            self.deliver(&held.event, &held.number, &held.message).await;
+        }
+    }
+
+    fn has_held_messages(&self) -> bool {
+        !self.held_messages.lock().unwrap().is_empty()
+    }
+
+    async fn send_message_to(&self, event: &str, numbers: &[String], message: &str) {
+        for number in numbers.iter() {
+            self.deliver(event, number, message).await;
+        }
+    }
+
+    async fn deliver(&self, event: &str, number: &str, message: &str) {
+        match self.notifier.send(number, message).await {
+            Ok(delivery) => {
+                if delivery.delivered {
+                    self.log_info(format!("Notification sent for: {}", number));
+                } else {
+                    self.log_error(format!("Notification couldn't send for: {}", number));
                 }
-                Err(e) => self.log_error(format_err!("Failure sending message, error: {:?}", e)),
+                self.record_history(event, number, delivery.sid, &delivery.status);
+            }
+            Err(e) => {
+                self.log_error(format_err!("Failure sending message, error: {:?}", e));
+                self.record_history(event, number, None, "failed");
+            }
+        }
+    }
+
+    fn record_history(&self, event: &str, number: &str, sid: Option<String>, status: &str) {
+        if let Some(history) = &self.history {
+            let notification = Notification {
+                game_id: self.game_id,
+                event: event.to_owned(),
+                recipient: number.to_owned(),
+                channel: self.notifier.channel().to_owned(),
+                sid,
+                status: status.to_owned(),
+                sent_at: Utc::now(),
+            };
+
+            if let Err(e) = history.record(&notification) {
+                self.log_error(format_err!(
+                    "Could not record notification history: {:?}",
+                    e
+                ));
             }
         }
     }
 
     async fn send_preview_notification(&self) {
-        let notification = format!(
-            "{} @ {} - {}\n\
-             \n\
-             {}",
-            self.home_team.name,
-            self.away_team.name,
-            self.local_datetime().format("%I:%M:%S %p"),
-            self.subhead(),
-        );
+        let mut values = self.score_values();
+        values.push(("subhead", self.subhead()));
 
-        self.send_message(&notification).await;
+        // Game time is shown in each subscriber's time zone
+        self.fan_out(EventKind::Preview, |sub| {
+            if !sub.wants(EventKind::Preview) {
+                return None;
+            }
+            let mut values = values.clone();
+            values.push((
+                "start_time",
+                sub.locale.start_time(sub.local_time(self.date)),
+            ));
+            Some(sub.message(MessageKind::Preview, &values))
+        })
+        .await;
     }
 
     fn check_end(&self, milestone_items: Vec<GameContentMilestoneItem>) -> bool {
@@ -260,6 +705,7 @@ impl Game {
             if item.r#type == "GOAL" {
                 let event_id = item.stats_event_id.parse::<u32>().ok();
                 let team_id = item.team_id.parse::<u32>().ok();
+                let scorer_id = item.player_id.parse::<u32>().ok();
                 let period_time =
                     NaiveTime::parse_from_str(&format!("00:{}", item.period_time), "%H:%M:%S").ok();
 
@@ -289,6 +735,10 @@ impl Game {
                     ordinal_num: item.ordinal_num,
                     period_time,
                     highlight: item.highlight,
+                    scorer_id,
+                    assist_ids: vec![],
+                    players_resolved: false,
+                    number: None,
                 };
 
                 goals.insert(goal.event_id, goal);
@@ -298,11 +748,82 @@ impl Game {
         goals
     }
 
+    /// Milestones don't list assists, so fill in the scorer and assists of new
+    /// or changed goals from the live feed. Goals whose description hasn't
+    /// changed keep the players already found for them.
+    async fn add_goal_players(
+        &self,
+        goals: &mut HashMap<u32, Goal>,
+        live_feed: &mut Option<LiveFeed>,
+    ) {
+        // New & changed goals, and any whose players weren't found last time
+        let mut needs_players: Vec<u32> = vec![];
+        for (id, goal) in goals.iter_mut() {
+            match self.goals.get(id) {
+                Some(stored_goal)
+                    if stored_goal.players_resolved
+                        && stored_goal.description == goal.description =>
+                {
+                    goal.scorer_id = stored_goal.scorer_id;
+                    goal.assist_ids = stored_goal.assist_ids.clone();
+                    goal.players_resolved = true;
+                }
+                _ => needs_players.push(*id),
+            }
+        }
+        if needs_players.is_empty() {
+            return;
+        }
+
+        if live_feed.is_none() {
+            *live_feed = self.get_live_feed().await;
+        }
+        let live_feed = match live_feed {
+            Some(live_feed) => live_feed,
+            None => return,
+        };
+
+        for play in live_feed.live_data.plays.all_plays.iter() {
+            if play.result.event_type_id != "GOAL" {
+                continue;
+            }
+            if let Some(goal) = goals.get_mut(&play.about.event_id) {
+                if let Some(scorer) = play.players_of_type("Scorer").next() {
+                    goal.scorer_id = Some(scorer.id);
+                }
+                goal.assist_ids = play.players_of_type("Assist").map(|p| p.id).collect();
+                goal.players_resolved = true;
+            }
+        }
+
+        for id in needs_players {
+            if goals.get(&id).is_some_and(|goal| !goal.players_resolved) {
+                self.log_warn(format!(
+                    "Goal {} isn't in the live feed yet, looking for its players again next time",
+                    id
+                ));
+            }
+        }
+    }
+
     async fn process_goals(&mut self, goals: &HashMap<u32, Goal>) {
-        if goals.is_empty() {
+        // No goals at all after some were notified is either every goal being
+        // overturned or a blank response, only the linescore can tell which
+        if goals.is_empty() && !self.goals.is_empty() && !self.linescore_is_scoreless().await {
             return;
         }
 
+        // Goals that no longer exist (goal is overturned). We will need to
+        // deduct these back from the score.
+        let missing: Vec<u32> = self
+            .goals
+            .keys()
+            .filter(|id| !goals.contains_key(id))
+            .cloned()
+            .collect();
+        self.missing_goals.retain(|id, _| missing.contains(id));
+        let goals_to_remove = self.confirm_overturned(missing).await;
+
         // Add any goals that don't yet exist in stored goals. We will need to add these
         // to the score.
         let goals_to_add: HashMap<u32, Goal> = goals
@@ -316,148 +837,586 @@ impl Game {
             })
             .collect();
 
-        // Remove any goals that no longer exist (goal is overturned). We will need
-        // to deduct these back from the score.
-        let goals_to_remove: HashMap<u32, Goal> = self
-            .goals
+        // Goals that still exist but were changed after review (new scorer, changed
+        // assists or credited to the other team).
+        let goals_to_update: HashMap<u32, Goal> = goals
             .iter()
-            .filter_map(|(id, stored_goal)| {
-                if goals.contains_key(id) {
-                    None
-                } else {
-                    Some((*id, stored_goal.clone()))
+            .filter_map(|(id, goal)| match self.goals.get(id) {
+                Some(stored_goal) if stored_goal.scoring_changed(goal) => Some((*id, goal.clone())),
+                _ => None,
+            })
+            .collect();
+
+        // Overturned goals are handled first so any goal scored since then is
+        // notified with the corrected score.
+        for (id, goal) in goals_to_remove {
+            self.deduct_goal_score(&goal);
+            self.notify_goal_disallowed(&goal).await;
+            self.notify_player_disallowed(&goal).await;
+
+            // Remove the goal from stored goals
+            self.goals.remove(&id);
+            self.save_state();
+        }
+
+        for (id, mut goal) in goals_to_update {
+            let stored_goal = self.goals.remove(&id);
+            goal.number = stored_goal
+                .as_ref()
+                .and_then(|stored_goal| stored_goal.number);
+            if let Some(stored_goal) = stored_goal.as_ref() {
+                if stored_goal.team_id != goal.team_id {
+                    self.deduct_goal_score(stored_goal);
+                    self.add_goal_score(&goal);
+                }
+            }
+            self.notify_scoring_change(&goal, stored_goal.as_ref())
+                .await;
+            self.notify_player_points(&goal, stored_goal.as_ref(), |sub| {
+                wants_scoring_change(sub, &goal, stored_goal.as_ref())
+            })
+            .await;
+
+            self.goals.insert(id, goal);
+            self.save_state();
+        }
+
+        // Reworded descriptions are kept so the players aren't looked up again
+        // each poll, nothing is notified for them
+        let mut reworded = false;
+        for (id, goal) in goals.iter() {
+            if let Some(stored_goal) = self.goals.get_mut(id) {
+                if stored_goal.description != goal.description
+                    && stored_goal.players_resolved
+                    && goal.players_resolved
+                    && !stored_goal.scoring_changed(goal)
+                {
+                    stored_goal.description = goal.description.clone();
+                    reworded = true;
+                }
+            }
+        }
+        if reworded {
+            self.save_state();
+        }
+
+        // Players found for goals that were notified before they were known
+        let goals_resolved: HashMap<u32, Goal> = goals
+            .iter()
+            .filter_map(|(id, goal)| match self.goals.get(id) {
+                Some(stored_goal)
+                    if !stored_goal.players_resolved
+                        && goal.players_resolved
+                        && !stored_goal.scoring_changed(goal) =>
+                {
+                    Some((*id, goal.clone()))
                 }
+                _ => None,
             })
             .collect();
 
-        for (id, goal) in goals_to_add {
+        for (id, mut goal) in goals_resolved {
+            let stored_goal = self.goals.remove(&id);
+            goal.number = stored_goal
+                .as_ref()
+                .and_then(|stored_goal| stored_goal.number);
+            self.notify_player_points(&goal, stored_goal.as_ref(), |sub| {
+                sub.wants_goal(goal.team_id)
+            })
+            .await;
+
+            self.goals.insert(id, goal);
+            self.save_state();
+        }
+
+        // In the order they were scored, so each is numbered after those before it
+        let mut goals_to_add: Vec<(u32, Goal)> = goals_to_add.into_iter().collect();
+        goals_to_add.sort_by_key(|(id, _)| *id);
+
+        for (id, mut goal) in goals_to_add {
+            goal.number = Some(self.goals.keys().filter(|other| **other < id).count() + 1);
             self.add_goal_score(&goal);
             self.notify_goal(&goal).await;
+            self.notify_player_points(&goal, None, |sub| sub.wants_goal(goal.team_id))
+                .await;
 
             self.goals.insert(id, goal);
+            self.save_state();
         }
+    }
 
-        for (id, goal) in goals_to_remove {
-            self.deduct_goal_score(&goal);
+    /// Goals missing from the milestones that are taken as overturned. A goal
+    /// can drop out of the milestones for a poll, so it's only overturned once
+    /// the linescore has gone down for its team or it's been missing for
+    /// `OVERTURN_POLLS` polls in a row.
+    async fn confirm_overturned(&mut self, missing: Vec<u32>) -> HashMap<u32, Goal> {
+        let mut overturned = HashMap::new();
+        if missing.is_empty() {
+            return overturned;
+        }
 
-            // Remove the goal from stored goals
-            self.goals.remove(&id);
+        // How many goals the linescore has taken away from each team
+        let (mut home_drop, mut away_drop) = match self.source.get_linescore(self.game_id).await {
+            Ok(linescore) => (
+                self.score.home.saturating_sub(linescore.teams.home.goals),
+                self.score.away.saturating_sub(linescore.teams.away.goals),
+            ),
+            Err(e) => {
+                self.log_warn(format_err!("Could not get linescore, error: {:?}", e));
+                (0, 0)
+            }
+        };
+
+        let mut missing = missing;
+        missing.sort();
+        for id in missing.into_iter().rev() {
+            let goal = match self.goals.get(&id) {
+                Some(goal) => goal,
+                None => continue,
+            };
+            let polls = self.missing_goals.entry(id).or_insert(0);
+            *polls += 1;
+
+            let drop = if goal.team_id == self.home_team.id {
+                &mut home_drop
+            } else {
+                &mut away_drop
+            };
+            let confirmed = if *drop > 0 {
+                *drop -= 1;
+                true
+            } else {
+                *polls >= OVERTURN_POLLS
+            };
+
+            if confirmed {
+                overturned.insert(id, goal.clone());
+                self.missing_goals.remove(&id);
+            } else {
+                self.log_info(format!(
+                    "Goal {} is missing from the milestones, waiting to see if it's overturned",
+                    id
+                ));
+            }
+        }
+
+        overturned
+    }
+
+    async fn linescore_is_scoreless(&self) -> bool {
+        match self.source.get_linescore(self.game_id).await {
+            Ok(linescore) => linescore.teams.home.goals == 0 && linescore.teams.away.goals == 0,
+            Err(e) => {
+                self.log_warn(format_err!("Could not get linescore, error: {:?}", e));
+                false
+            }
         }
     }
 
     fn add_goal_score(&mut self, goal: &Goal) {
         let scoring_team = goal.team_id;
         if scoring_team == self.home_team.id {
-            self.score.home += 1;
+            GameScore::add(&mut self.score.home, &mut self.score.home_correction);
         } else {
-            self.score.away += 1;
+            GameScore::add(&mut self.score.away, &mut self.score.away_correction);
         }
     }
 
     fn deduct_goal_score(&mut self, goal: &Goal) {
         let scoring_team = goal.team_id;
         if scoring_team == self.home_team.id {
-            self.score.home -= 1;
+            GameScore::deduct(&mut self.score.home, &mut self.score.home_correction);
         } else {
-            self.score.away -= 1;
+            GameScore::deduct(&mut self.score.away, &mut self.score.away_correction);
         }
     }
 
-    async fn notify_goal(&self, goal: &Goal) {
-        let scoring_team_name = if goal.team_id == self.home_team.id {
-            &self.home_team.team_name
-        } else {
-            &self.away_team.team_name
+    /// Check the score against the official linescore. If they still disagree
+    /// after `SCORE_MISMATCH_GRACE`, the linescore wins and a correction is sent.
+    async fn reconcile_score(&mut self) {
+        if let Some(checked) = self.linescore_checked {
+            if self.since(checked) < LINESCORE_INTERVAL {
+                return;
+            }
+        }
+        self.linescore_checked = Some(self.source.now());
+
+        let linescore = match self.source.get_linescore(self.game_id).await {
+            Ok(linescore) => linescore,
+            Err(e) => {
+                self.log_warn(format_err!("Could not get linescore, error: {:?}", e));
+                return;
+            }
         };
 
-        let message = format!(
-            "{} score\n\
-             \n\
-             {} {}, {} {} - {} {}\n\
-             \n\
-             {}",
-            scoring_team_name,
-            goal.period_time.format("%M:%S"),
-            goal.ordinal_num,
+        // Shootout attempts show up as goal milestones but only the deciding
+        // goal counts, which is settled in the final reconcile.
+        if linescore.current_period_ordinal.as_deref() == Some("SO") {
+            return;
+        }
+
+        let (home, away) = (linescore.teams.home.goals, linescore.teams.away.goals);
+        if home == self.score.home && away == self.score.away {
+            self.score_mismatch_since = None;
+            return;
+        }
+
+        let now = self.source.now();
+        let mismatch_since = *self.score_mismatch_since.get_or_insert(now);
+        self.log_warn(format!(
+            "Score mismatch, milestones have {} {} - {} {}, linescore has {} {} - {} {}",
             self.home_team.abbreviation,
             self.score.home,
             self.away_team.abbreviation,
             self.score.away,
-            goal.description
-        );
-
-        self.log_info(format!(
-            "{} score, {} {}, {} {} - {} {}, {}",
-            scoring_team_name,
-            goal.period_time.format("%M:%S"),
-            goal.ordinal_num,
             self.home_team.abbreviation,
-            self.score.home,
+            home,
             self.away_team.abbreviation,
-            self.score.away,
-            goal.description
+            away,
         ));
 
-        self.send_message(&message).await;
+        if self.since(mismatch_since) < SCORE_MISMATCH_GRACE {
+            return;
+        }
+
+        self.score_mismatch_since = None;
+        self.score.correct(home, away);
+        self.notify_score_correction().await;
     }
 
-    async fn process_highlights(&mut self, goals: &HashMap<u32, Goal>) {
-        let highlights: HashMap<u32, GameContentMilestoneItemHighlight> = goals
-            .clone()
-            .into_iter()
-            .filter_map(|(id, goal)| {
-                if let Some(highlight) = goal.highlight {
-                    Some((id, highlight))
-                } else {
-                    None
+    /// Settle the score against the linescore once the game is over, so the
+    /// final message is always right. No correction notice is sent.
+    async fn reconcile_final_score(&mut self) {
+        match self.source.get_linescore(self.game_id).await {
+            Ok(linescore) => {
+                let (home, away) = (linescore.teams.home.goals, linescore.teams.away.goals);
+                if home != self.score.home || away != self.score.away {
+                    self.log_warn(format!(
+                        "Final score mismatch, milestones have {} {} - {} {}, linescore has {} {} - {} {}",
+                        self.home_team.abbreviation,
+                        self.score.home,
+                        self.away_team.abbreviation,
+                        self.score.away,
+                        self.home_team.abbreviation,
+                        home,
+                        self.away_team.abbreviation,
+                        away,
+                    ));
+                    self.score.correct(home, away);
                 }
-            })
-            .collect();
-
-        for (id, highlight) in highlights {
-            if !self.highlights_notified.contains(&id) {
-                if let Err(e) = self.notify_highlight(highlight).await {
-                    self.log_error(e);
-                    return;
-                };
-                self.highlights_notified.push(id);
             }
+            Err(e) => self.log_warn(format_err!("Could not get linescore, error: {:?}", e)),
         }
     }
 
-    async fn notify_highlight(
-        &self,
-        highlight: GameContentMilestoneItemHighlight,
-    ) -> Result<(), Error> {
-        if let Some(playback) = highlight.playbacks {
-            let mut clip_1800k = None;
-            for clip in playback {
-                if clip.name == "FLASH_1800K_896x504" {
-                    clip_1800k = Some(clip)
-                }
-            }
+    async fn notify_score_correction(&self) {
+        self.log_info(format!(
+            "Score correction, {} {} - {} {}",
+            self.home_team.abbreviation,
+            self.score.home,
+            self.away_team.abbreviation,
+            self.score.away
+        ));
 
-            if let Some(clip) = clip_1800k {
-                let message = format!(
-                    "Highlight\n\
-                     \n\
-                     {}\n\
-                     \n\
-                     {}",
-                    highlight.description, clip.url
-                );
+        self.send_templated(
+            EventKind::ScoreCorrection,
+            MessageKind::ScoreCorrection,
+            |_| self.score_values(),
+        )
+        .await;
+    }
+
+    fn team_name(&self, team_id: u32) -> &str {
+        &self.team(team_id).team_name
+    }
+
+    fn other_team_id(&self, team_id: u32) -> u32 {
+        if team_id == self.home_team.id {
+            self.away_team.id
+        } else {
+            self.home_team.id
+        }
+    }
+
+    /// Whose goal it was, from the subscriber's point of view
+    fn team(&self, team_id: u32) -> &Team {
+        if team_id == self.home_team.id {
+            &self.home_team
+        } else {
+            &self.away_team
+        }
+    }
+
+    /// Placeholder values every message has
+    fn score_values(&self) -> Vec<(&'static str, String)> {
+        score_values(&self.home_team, &self.away_team, &self.score)
+    }
+
+    /// Placeholder values for messages about a goal
+    fn goal_values(&self, goal: &Goal, locale: Locale) -> Vec<(&'static str, String)> {
+        let mut values = self.score_values();
+        values.extend(goal_values(
+            goal,
+            self.team(goal.team_id),
+            self.team(self.other_team_id(goal.team_id)),
+            locale,
+        ));
+        values
+    }
+
+    fn goal_headline(&self, sub: &Subscriber, team_id: u32) -> String {
+        goal_headline(
+            sub,
+            self.team(team_id),
+            self.team(self.other_team_id(team_id)),
+        )
+    }
+
+    /// Goal time, score & description, for logging
+    fn goal_summary(&self, goal: &Goal) -> String {
+        format!(
+            "{} {}, {} {} - {} {}, {}",
+            goal.period_time.format("%M:%S"),
+            goal.ordinal_num,
+            self.home_team.abbreviation,
+            self.score.home,
+            self.away_team.abbreviation,
+            self.score.away,
+            goal.description
+        )
+    }
+
+    async fn notify_goal(&self, goal: &Goal) {
+        self.log_info(format!(
+            "{} score, {}",
+            self.team_name(goal.team_id),
+            self.goal_summary(goal)
+        ));
 
+        // Each subscriber gets it as whichever of goals for & against they
+        // want it as, which is what's recorded in history
+        for event in [EventKind::GoalsFor, EventKind::GoalsAgainst].iter() {
+            self.fan_out(*event, |sub| {
+                if sub.goal_event(goal.team_id) != Some(*event) {
+                    return None;
+                }
+                Some(goal_message(
+                    sub,
+                    &self.goal_headline(sub, goal.team_id),
+                    goal,
+                    self.team(goal.team_id),
+                    self.team(self.other_team_id(goal.team_id)),
+                    &self.score_values(),
+                ))
+            })
+            .await;
+        }
+    }
+
+    async fn notify_goal_disallowed(&self, goal: &Goal) {
+        self.log_info(format!(
+            "{} goal disallowed, {}",
+            self.team_name(goal.team_id),
+            self.goal_summary(goal)
+        ));
+
+        self.fan_out(EventKind::Disallowed, |sub| {
+            if !sub.wants_goal_update(EventKind::Disallowed, goal.team_id) {
+                return None;
+            }
+            Some(sub.message(MessageKind::Disallowed, &self.goal_values(goal, sub.locale)))
+        })
+        .await;
+    }
+
+    async fn notify_scoring_change(&self, goal: &Goal, previous: Option<&Goal>) {
+        self.log_info(format!(
+            "Scoring change, {} goal, {}",
+            self.team_name(goal.team_id),
+            self.goal_summary(goal)
+        ));
+
+        self.fan_out(EventKind::ScoringChange, |sub| {
+            if !wants_scoring_change(sub, goal, previous) {
+                return None;
+            }
+            Some(sub.message(
+                MessageKind::ScoringChange,
+                &self.goal_values(goal, sub.locale),
+            ))
+        })
+        .await;
+    }
+
+    /// Notify player subscriptions of their player's points on this goal. For a
+    /// goal changed after review, only newly credited points are notified.
+    /// Numbers a team subscription already tells about the goal, those
+    /// `team_wants`, are skipped.
+    async fn notify_player_points<F>(&self, goal: &Goal, previous: Option<&Goal>, team_wants: F)
+    where
+        F: Fn(&Subscriber) -> bool,
+    {
+        for sub in self.player_subscriptions.iter() {
+            let point = match goal.player_point(sub.player_id) {
+                Some(point) => point,
+                None => continue,
+            };
+            if !sub.wants(point) {
+                continue;
+            }
+            if previous.and_then(|previous| previous.player_point(sub.player_id)) == Some(point) {
+                continue;
+            }
+
+            let mut values = self.score_values();
+            values.extend(vec![
+                ("player", sub.name.clone()),
+                ("point", point.name(sub.locale).to_owned()),
+                ("period_time", goal.period_time.format("%M:%S").to_string()),
+                ("period", sub.locale.period(&goal.ordinal_num)),
+                ("description", goal.description.clone()),
+            ]);
+            let message = sub
+                .templates
+                .render(MessageKind::PlayerPoint, sub.locale, &values);
+
+            self.log_info(format!(
+                "{} {}, {} {}, {}",
+                sub.name,
+                point,
+                goal.period_time.format("%M:%S"),
+                goal.ordinal_num,
+                goal.description
+            ));
+
+            let numbers = self.without_team_subscribers(&sub.numbers, &team_wants);
+            self.send_message_to(point.event_name(), &numbers, &message)
+                .await;
+        }
+    }
+
+    /// Tell player subscriptions a goal they were notified of was disallowed,
+    /// unless a team subscription already did
+    async fn notify_player_disallowed(&self, goal: &Goal) {
+        for sub in self.player_subscriptions.iter() {
+            match goal.player_point(sub.player_id) {
+                Some(point) if sub.wants(point) => {}
+                _ => continue,
+            }
+
+            let message = sub.templates.render(
+                MessageKind::Disallowed,
+                sub.locale,
+                &self.goal_values(goal, sub.locale),
+            );
+            let numbers = self.without_team_subscribers(&sub.numbers, |sub| {
+                sub.wants_goal_update(EventKind::Disallowed, goal.team_id)
+            });
+            self.send_message_to(EventKind::Disallowed.name(), &numbers, &message)
+                .await;
+        }
+    }
+
+    /// `numbers` less those a team subscription to this game sends the same
+    /// news, so no one gets two texts about one goal
+    fn without_team_subscribers<F>(&self, numbers: &[String], team_wants: F) -> Vec<String>
+    where
+        F: Fn(&Subscriber) -> bool,
+    {
+        numbers
+            .iter()
+            .filter(|number| {
+                !self
+                    .subscriptions
+                    .iter()
+                    .any(|sub| &sub.number == *number && team_wants(sub))
+            })
+            .cloned()
+            .collect()
+    }
+
+    async fn process_highlights(&mut self, goals: &HashMap<u32, Goal>) {
+        let highlights: HashMap<u32, GameContentMilestoneItemHighlight> = goals
+            .clone()
+            .into_iter()
+            .filter_map(|(id, goal)| {
+                if let Some(highlight) = goal.highlight {
+                    Some((id, highlight))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        for (id, highlight) in highlights {
+            if !self.highlights_notified.contains(&id) {
+                let goal_number = self
+                    .goals
+                    .get(&id)
+                    .and_then(|goal| goal.number)
+                    .unwrap_or_else(|| goals.keys().filter(|other| **other <= id).count());
+
+                // The clip may not be published yet, it's tried again next time
+                if let Err(e) = self.notify_highlight(goal_number, highlight).await {
+                    self.log_warn(e);
+                    continue;
+                };
+                self.highlights_notified.push(id);
+                self.save_state();
+            }
+        }
+    }
+
+    /// Send a goal's highlight, either on its own or, for subscribers that
+    /// follow up goals, as a short message naming the goal by its number
+    async fn notify_highlight(
+        &self,
+        goal_number: usize,
+        highlight: GameContentMilestoneItemHighlight,
+    ) -> Result<(), Error> {
+        if let Some(playbacks) = highlight.playbacks.as_ref() {
+            if let Some(clip) = choose_playback(&self.highlight_renditions, playbacks) {
                 self.log_info(format!(
                     "Highlight, {}, {}",
                     highlight.description, clip.url
                 ));
 
-                self.send_message(&message).await;
+                let mut values = self.score_values();
+                values.push(("description", highlight.description.clone()));
+                values.push(("url", self.short_url(&clip.url).await));
+                let mut follow_up_values = values.clone();
+                follow_up_values.push(("goal_number", goal_number.to_string()));
+
+                self.fan_out(EventKind::Highlights, |sub| {
+                    if !sub.wants(EventKind::Highlights) {
+                        return None;
+                    }
+                    match sub.highlight_mode {
+                        HighlightMode::Separate => {
+                            Some(sub.message(MessageKind::Highlight, &values))
+                        }
+                        HighlightMode::FollowUp => {
+                            Some(sub.message(MessageKind::HighlightFollowUp, &follow_up_values))
+                        }
+                    }
+                })
+                .await;
 
                 return Ok(());
             }
         };
-        bail!("No playback clip available");
+        bail!("No playback clip available for: {}", highlight.description);
+    }
+
+    /// `url` shortened if short links are set up, otherwise as is
+    async fn short_url(&self, url: &str) -> String {
+        if let Some(shortener) = &self.shortener {
+            match shortener.shorten(url).await {
+                Ok(short_url) => return short_url,
+                Err(e) => self.log_warn(format_err!("Could not shorten {}: {}", url, e)),
+            }
+        }
+        url.to_owned()
     }
 
     async fn run(&mut self) {
@@ -469,56 +1428,231 @@ impl Game {
             self.local_datetime().to_rfc2822()
         );
 
+        let mut recipients: Vec<&str> = self
+            .subscriptions
+            .iter()
+            .map(|sub| sub.number.as_str())
+            .chain(
+                self.player_subscriptions
+                    .iter()
+                    .flat_map(|sub| sub.numbers.iter().map(String::as_str)),
+            )
+            .collect();
+        recipients.sort();
+        recipients.dedup();
+        self.log_info(format!("Notifying: {}", recipients.join(", ")));
+
         loop {
+            self.send_held_messages().await;
+
             match self.status {
                 GameStatus::Scheduled => {
                     self.run_scheduled_game().await;
-                    task::sleep(Duration::from_secs(60 * 10)).await;
+                    self.sleep(Duration::from_secs(60 * 10)).await;
                 }
                 GameStatus::Live => {
                     self.run_live_game().await;
-                    task::sleep(Duration::from_secs(10)).await;
+                    self.sleep(Duration::from_secs(10)).await;
                 }
-                GameStatus::Ended => break,
+                GameStatus::Ended => {
+                    self.reconcile_final_score().await;
+                    self.send_final_notification().await;
+                    self.status = GameStatus::Final;
+                }
+                GameStatus::Final => {
+                    self.run_recap().await;
+                    self.status = GameStatus::Done;
+                }
+                GameStatus::Done => break,
             }
+
+            self.save_state();
         }
 
-        let winning_team_name = if self.score.home > self.score.away {
-            self.home_team.team_name.clone()
+        // Stick around to deliver anything held for quiet hours
+        while self.has_held_messages() {
+            self.sleep(Duration::from_secs(60)).await;
+            self.send_held_messages().await;
+        }
+    }
+
+    async fn send_final_notification(&self) {
+        let winning_team = if self.score.home > self.score.away {
+            &self.home_team
         } else {
-            self.away_team.team_name.clone()
+            &self.away_team
         };
+        let winning_team_id = winning_team.id;
+        let winning_team_name = winning_team.team_name.clone();
+
+        let mut values = self.score_values();
+        values.push(("winning_team", winning_team_name.clone()));
+        values.push((
+            "losing_team",
+            self.team_name(self.other_team_id(winning_team_id))
+                .to_owned(),
+        ));
 
-        let message = format!(
-            "{} win\n\nFinal score: {} {} - {} {}",
+        self.log_info(format!(
+            "{} win. Final score: {} {} - {} {}",
             winning_team_name,
             self.home_team.abbreviation,
             self.score.home,
             self.away_team.abbreviation,
             self.score.away
-        );
+        ));
+
+        self.fan_out(EventKind::Final, |sub| {
+            if sub.wants_final(winning_team_id) {
+                Some(sub.message(MessageKind::Final, &values))
+            } else {
+                None
+            }
+        })
+        .await;
+    }
+
+    /// Wait for the recap article and three stars to be published, then send
+    /// the recap. Gives up waiting after `RECAP_ATTEMPTS` and sends what's there.
+    async fn run_recap(&mut self) {
+        if !self
+            .subscriptions
+            .iter()
+            .any(|sub| sub.wants(EventKind::Recap))
+        {
+            self.log_info("No one wants the recap, not waiting for it");
+            return;
+        }
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+
+            let recap = self.get_recap().await;
+            match self.source.get_live_feed(self.game_id).await {
+                Ok(live_feed) => {
+                    let three_stars = live_feed.live_data.decisions.three_stars();
+                    if (recap.is_ok() && three_stars.is_some()) || attempts >= RECAP_ATTEMPTS {
+                        self.send_recap_notification(&live_feed, recap.ok()).await;
+                        return;
+                    }
+                }
+                Err(e) => {
+                    self.log_warn(format_err!("Could not get live feed, error: {:?}", e));
+                    if attempts >= RECAP_ATTEMPTS {
+                        self.log_error("No box score available, recap not sent");
+                        return;
+                    }
+                }
+            }
 
+            self.log_info("Recap not available yet, sleeping...");
+            self.sleep(RECAP_INTERVAL).await;
+        }
+    }
+
+    async fn send_recap_notification(
+        &self,
+        live_feed: &LiveFeed,
+        recap: Option<GameContentEditorialItemArticle>,
+    ) {
         self.log_info(format!(
-            "{} win. Final score: {} {} - {} {}",
-            winning_team_name,
+            "Recap, {}",
+            recap
+                .as_ref()
+                .map(|recap| recap.headline.as_str())
+                .unwrap_or("no recap article")
+        ));
+
+        self.send_localized(EventKind::Recap, |locale| {
+            self.recap_message(live_feed, recap.as_ref(), locale)
+        })
+        .await;
+    }
+
+    fn recap_message(
+        &self,
+        live_feed: &LiveFeed,
+        recap: Option<&GameContentEditorialItemArticle>,
+        locale: Locale,
+    ) -> String {
+        let (title, shots, power_play, saves_label, three_stars) = match locale {
+            Locale::En => ("Recap", "Shots", "Power play", "Saves", "Three stars"),
+            Locale::Fr => (
+                "Résumé",
+                "Tirs",
+                "Avantage numérique",
+                "Arrêts",
+                "Trois étoiles",
+            ),
+        };
+
+        let home = &live_feed.live_data.boxscore.teams.home;
+        let away = &live_feed.live_data.boxscore.teams.away;
+        let home_stats = &home.team_stats.team_skater_stats;
+        let away_stats = &away.team_stats.team_skater_stats;
+
+        let mut message = format!(
+            "{}\n\
+             \n\
+             {} {} - {} {}\n\
+             \n\
+             {}: {} {} - {} {}\n\
+             {}: {} {}/{} - {} {}/{}",
+            title,
             self.home_team.abbreviation,
             self.score.home,
             self.away_team.abbreviation,
-            self.score.away
-        ));
+            self.score.away,
+            shots,
+            self.home_team.abbreviation,
+            home_stats.shots,
+            self.away_team.abbreviation,
+            away_stats.shots,
+            power_play,
+            self.home_team.abbreviation,
+            home_stats.power_play_goals,
+            home_stats.power_play_opportunities,
+            self.away_team.abbreviation,
+            away_stats.power_play_goals,
+            away_stats.power_play_opportunities,
+        );
+
+        let saves: Vec<String> = goalie_saves(home)
+            .into_iter()
+            .chain(goalie_saves(away))
+            .collect();
+        if !saves.is_empty() {
+            message.push_str(&format!("\n{}: {}", saves_label, saves.join(", ")));
+        }
 
-        self.send_message(&message).await;
+        if let Some(stars) = live_feed.live_data.decisions.three_stars() {
+            message.push_str(&format!(
+                "\n\n{}:\n1. {}\n2. {}\n3. {}",
+                three_stars, stars[0].full_name, stars[1].full_name, stars[2].full_name
+            ));
+        }
+
+        if let Some(recap) = recap {
+            message.push_str(&format!(
+                "\n\n{}\n{}",
+                recap.headline,
+                article_url(&recap.url)
+            ));
+        }
+
+        message
     }
 
     async fn run_scheduled_game(&mut self) {
         // Don't try to get preview until earliest notification time.
-        if Local::now().time() < self.earliest_notification {
+        if self.source.now() < self.notify_after.with_timezone(&Utc) {
             self.log_info("Before notification time, sleeping...");
             return;
         }
 
         // Once preview is sent, then move on to checking if game has started
-        if self.preview.is_none() {
+        if !self.preview_sent {
             // After time is passed, try to get preview. Don't proceed until
             // preview article is fetched.
             if let Err(e) = self.get_preview().await {
@@ -529,6 +1663,7 @@ impl Game {
             // Now that preview is fetched, send out notification
             self.log_info(format!("Got preview: {}", self.subhead()));
             self.send_preview_notification().await;
+            self.preview_sent = true;
         } else {
             // Check stream start time in milestone struct, if populated, game
             // has started. Change game to Live which will progress game forward.
@@ -546,12 +1681,135 @@ impl Game {
         }
     }
 
+    /// Send the puck drop once the 1st period is under way, when the
+    /// starting goalies are in the box score. Games picked up later on, ie.
+    /// after a restart, don't get one.
+    async fn check_puck_drop(&mut self, live_feed: &LiveFeed) {
+        match live_feed.live_data.linescore.current_period {
+            0 => return,
+            1 => {
+                // Broadcasts are nice to have, the puck drop still goes out without them
+                let broadcasts = match self.source.get_broadcasts(self.game_id).await {
+                    Ok(broadcasts) => broadcasts,
+                    Err(e) => {
+                        self.log_warn(format_err!("Could not get broadcasts, error: {:?}", e));
+                        vec![]
+                    }
+                };
+                self.notify_puck_drop(live_feed, &broadcasts).await;
+            }
+            _ => self.log_info("Past the 1st period, not sending puck drop"),
+        }
+
+        self.puck_drop_sent = true;
+        self.save_state();
+    }
+
+    async fn notify_puck_drop(&self, live_feed: &LiveFeed, broadcasts: &[Broadcast]) {
+        let teams = &live_feed.live_data.boxscore.teams;
+        let home_goalie = starting_goalie(&teams.home);
+        let away_goalie = starting_goalie(&teams.away);
+
+        self.log_info(format!(
+            "Puck drop, starting goalies: {} {}, {} {}",
+            self.home_team.abbreviation,
+            home_goalie.unwrap_or("unknown"),
+            self.away_team.abbreviation,
+            away_goalie.unwrap_or("unknown")
+        ));
+
+        let values = self.score_values();
+        self.fan_out(EventKind::PuckDrop, |sub| {
+            if !sub.wants(EventKind::PuckDrop) {
+                return None;
+            }
+
+            let unknown = match sub.locale {
+                Locale::En => "TBD",
+                Locale::Fr => "à confirmer",
+            };
+            let broadcasts = self.broadcasts_for(sub, broadcasts);
+            let broadcasts = if broadcasts.is_empty() {
+                String::from(match sub.locale {
+                    Locale::En => "not available",
+                    Locale::Fr => "non disponible",
+                })
+            } else {
+                broadcasts.join(", ")
+            };
+
+            let mut values = values.clone();
+            values.push(("home_goalie", home_goalie.unwrap_or(unknown).to_owned()));
+            values.push(("away_goalie", away_goalie.unwrap_or(unknown).to_owned()));
+            values.push(("broadcasts", broadcasts));
+            Some(sub.message(MessageKind::PuckDrop, &values))
+        })
+        .await;
+    }
+
+    /// Names of the broadcasts in a subscriber's market, the national ones
+    /// and those of the teams they follow. Subscribers that don't follow
+    /// either team, ie. league wide, get every broadcast. Only those in the
+    /// subscriber's language are given, unless there are none.
+    fn broadcasts_for<'a>(&self, sub: &Subscriber, broadcasts: &'a [Broadcast]) -> Vec<&'a str> {
+        let follows_home = sub.follows(self.home_team.id);
+        let follows_away = sub.follows(self.away_team.id);
+
+        let in_market: Vec<&Broadcast> = broadcasts
+            .iter()
+            .filter(|broadcast| match broadcast.market.as_str() {
+                "home" => follows_home || !follows_away,
+                "away" => follows_away || !follows_home,
+                _ => true,
+            })
+            .collect();
+        let in_language: Vec<&Broadcast> = in_market
+            .iter()
+            .filter(|broadcast| broadcast.language == sub.locale.language())
+            .cloned()
+            .collect();
+
+        let mut names: Vec<&str> = vec![];
+        let shown = if in_language.is_empty() {
+            in_market
+        } else {
+            in_language
+        };
+        for broadcast in shown {
+            if !names.contains(&broadcast.name.as_str()) {
+                names.push(&broadcast.name);
+            }
+        }
+        names
+    }
+
+    async fn get_live_feed(&self) -> Option<LiveFeed> {
+        match self.source.get_live_feed(self.game_id).await {
+            Ok(live_feed) => Some(live_feed),
+            Err(e) => {
+                self.log_warn(format_err!("Could not get live feed, error: {:?}", e));
+                None
+            }
+        }
+    }
+
     async fn run_live_game(&mut self) {
+        // Fetched at most once a poll, for the puck drop and goal players
+        let mut live_feed = None;
+        if !self.puck_drop_sent {
+            live_feed = self.get_live_feed().await;
+            if let Some(live_feed) = &live_feed {
+                self.check_puck_drop(live_feed).await;
+            }
+        }
+
         if let Ok(items) = self.get_milestone_items().await {
-            let goals = self.parse_goals(items.clone());
+            let mut goals = self.parse_goals(items.clone());
+            self.add_goal_players(&mut goals, &mut live_feed).await;
 
             self.process_goals(&goals).await;
             self.process_highlights(&goals).await;
+            self.reconcile_score().await;
 
             if self.check_end(items) {
                 self.status = GameStatus::Ended;
@@ -560,7 +1818,38 @@ impl Game {
     }
 }
 
-fn log_words<'a, T>(vec: &[T]) -> (&'a str, &'a str) {
+/// Whether a subscriber wants a scoring change to `goal`, having wanted the
+/// goal as it is now or as it was before the change
+fn wants_scoring_change(sub: &Subscriber, goal: &Goal, previous: Option<&Goal>) -> bool {
+    sub.wants_goal_update(EventKind::ScoringChange, goal.team_id)
+        || previous.is_some_and(|previous| {
+            sub.wants_goal_update(EventKind::ScoringChange, previous.team_id)
+        })
+}
+
+/// The latest `earliest_notification_time` at or before `time`
+pub fn notification_time_before(
+    time: DateTime<Local>,
+    earliest_notification_time: NaiveTime,
+) -> DateTime<Local> {
+    let notification_time = Local
+        .from_local_datetime(
+            &time
+                .naive_local()
+                .date()
+                .and_time(earliest_notification_time),
+        )
+        .earliest()
+        .unwrap_or(time);
+
+    if notification_time > time {
+        notification_time - chrono::Duration::days(1)
+    } else {
+        notification_time
+    }
+}
+
+pub fn log_words<'a, T>(vec: &[T]) -> (&'a str, &'a str) {
     let verb = {
         if vec.len() != 1 {
             "are"
@@ -578,30 +1867,397 @@ fn log_words<'a, T>(vec: &[T]) -> (&'a str, &'a str) {
     (verb, plural)
 }
 
-#[derive(PartialEq)]
+/// Name of the goalie who started, the first one in the box score with time
+/// on ice. None until one has played.
+fn starting_goalie(team: &BoxscoreTeam) -> Option<&str> {
+    team.goalies
+        .iter()
+        .filter_map(|id| team.player(*id))
+        .find(|player| {
+            player
+                .stats
+                .goalie_stats
+                .as_ref()
+                .is_some_and(|stats| has_played(&stats.time_on_ice))
+        })
+        .map(|player| player.person.full_name.as_str())
+}
+
+/// Whether a "mm:ss" time on ice is more than none
+fn has_played(time_on_ice: &str) -> bool {
+    time_on_ice.chars().any(|c| c.is_ascii_digit() && c != '0')
+}
+
+/// "Name saves/shots" for each goalie that played
+fn goalie_saves(team: &BoxscoreTeam) -> Vec<String> {
+    team.goalies
+        .iter()
+        .filter_map(|id| team.player(*id))
+        .filter_map(|player| {
+            player.stats.goalie_stats.as_ref().map(|stats| {
+                format!(
+                    "{} {}/{}",
+                    player.person.full_name, stats.saves, stats.shots
+                )
+            })
+        })
+        .collect()
+}
+
+/// Goal headline from the subscriber's point of view
+fn goal_headline(sub: &Subscriber, scoring_team: &Team, other_team: &Team) -> String {
+    if sub.locale == Locale::Fr {
+        return if sub.follows(other_team.id) {
+            format!(
+                "But des {} contre les {}",
+                scoring_team.team_name, other_team.team_name
+            )
+        } else {
+            format!("But des {}", scoring_team.team_name)
+        };
+    }
+
+    match (sub.follows(scoring_team.id), sub.follows(other_team.id)) {
+        (true, true) => format!(
+            "{} score vs. {}",
+            scoring_team.team_name, other_team.team_name
+        ),
+        (false, true) => format!(
+            "{} score against {}",
+            scoring_team.team_name, other_team.team_name
+        ),
+        _ => format!("{} score", scoring_team.team_name),
+    }
+}
+
+fn score_values(home: &Team, away: &Team, score: &GameScore) -> Vec<(&'static str, String)> {
+    vec![
+        ("home_name", home.name.clone()),
+        ("away_name", away.name.clone()),
+        ("home_abbr", home.abbreviation.clone()),
+        ("away_abbr", away.abbreviation.clone()),
+        ("score_home", score.home.to_string()),
+        ("score_away", score.away.to_string()),
+    ]
+}
+
+fn goal_values(
+    goal: &Goal,
+    scoring_team: &Team,
+    other_team: &Team,
+    locale: Locale,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("scoring_team", scoring_team.team_name.clone()),
+        ("other_team", other_team.team_name.clone()),
+        ("period_time", goal.period_time.format("%M:%S").to_string()),
+        ("period", locale.period(&goal.ordinal_num)),
+        ("description", goal.description.clone()),
+    ]
+}
+
+/// `score_values` are for the game the goal was scored in
+fn goal_message(
+    sub: &Subscriber,
+    headline: &str,
+    goal: &Goal,
+    scoring_team: &Team,
+    other_team: &Team,
+    score_values: &[(&'static str, String)],
+) -> String {
+    let mut values = score_values.to_vec();
+    values.extend(goal_values(goal, scoring_team, other_team, sub.locale));
+    values.push(("headline", headline.to_owned()));
+    sub.message(MessageKind::Goal, &values)
+}
+
+/// Goal message exactly as `sub` would get it, for a made up goal by
+/// `scoring_team` at home against `other_team`
+pub fn sample_goal_message(sub: &Subscriber, scoring_team: &Team, other_team: &Team) -> String {
+    let goal = Goal {
+        event_id: 0,
+        team_id: scoring_team.id,
+        description: String::from(
+            "Test Player (1) Wrist Shot, assists: Test Assist (1), Test Assist (1)",
+        ),
+        ordinal_num: String::from("2nd"),
+        period_time: NaiveTime::from_hms_opt(0, 12, 34).unwrap(),
+        highlight: None,
+        scorer_id: None,
+        assist_ids: vec![],
+        players_resolved: false,
+        number: None,
+    };
+    let mut score = GameScore::new();
+    score.home = 1;
+
+    goal_message(
+        sub,
+        &goal_headline(sub, scoring_team, other_team),
+        &goal,
+        scoring_team,
+        other_team,
+        &score_values(scoring_team, other_team, &score),
+    )
+}
+
+/// Editorial urls are relative to nhl.com
+fn article_url(url: &str) -> String {
+    if url.starts_with('/') {
+        format!("https://www.nhl.com{}", url)
+    } else {
+        url.to_owned()
+    }
+}
+
+/// Everything needed to resume a game after a restart
+#[derive(Serialize, Deserialize)]
+struct GameState {
+    status: GameStatus,
+    preview_sent: bool,
+    /// Not in state saved before puck drop notifications existed
+    #[serde(default)]
+    puck_drop_sent: bool,
+    score: GameScore,
+    goals: HashMap<u32, Goal>,
+    highlights_notified: Vec<u32>,
+    /// Messages waiting on quiet hours, so a restart doesn't lose them
+    #[serde(default)]
+    held_messages: Vec<HeldMessage>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum GameStatus {
     Scheduled,
     Live,
+    /// Game is over, final score not sent yet
     Ended,
+    /// Final score sent, waiting on the recap
+    Final,
+    Done,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 struct GameScore {
     home: u8,
     away: u8,
+    /// Goals the linescore had that milestones didn't when the score was last
+    /// corrected (negative if milestones had extra). Late milestones are
+    /// absorbed by this instead of being counted twice.
+    home_correction: i8,
+    away_correction: i8,
 }
 
 impl GameScore {
     fn new() -> Self {
-        GameScore { home: 0, away: 0 }
+        GameScore {
+            home: 0,
+            away: 0,
+            home_correction: 0,
+            away_correction: 0,
+        }
+    }
+
+    fn add(score: &mut u8, correction: &mut i8) {
+        if *correction > 0 {
+            *correction -= 1;
+        } else {
+            *score += 1;
+        }
     }
+
+    fn deduct(score: &mut u8, correction: &mut i8) {
+        if *correction < 0 {
+            *correction += 1;
+        } else {
+            *score = score.saturating_sub(1);
+        }
+    }
+
+    fn correct(&mut self, home: u8, away: u8) {
+        self.home_correction += home as i8 - self.home as i8;
+        self.away_correction += away as i8 - self.away as i8;
+        self.home = home;
+        self.away = away;
+    }
+}
+
+/// Message held until a subscriber's quiet hours end
+#[derive(Clone, Serialize, Deserialize)]
+struct HeldMessage {
+    event: String,
+    number: String,
+    message: String,
+    until: DateTime<Utc>,
 }
 
 #[derive(Clone)]
+pub struct PlayerSubscription {
+    player_id: u32,
+    pub name: String,
+    pub team_id: u32,
+    events: PlayerEvents,
+    pub numbers: Vec<String>,
+    templates: Templates,
+    locale: Locale,
+}
+
+impl PlayerSubscription {
+    fn wants(&self, point: PlayerPoint) -> bool {
+        match self.events {
+            PlayerEvents::Goals => point == PlayerPoint::Goal,
+            PlayerEvents::Assists => point == PlayerPoint::Assist,
+            PlayerEvents::Both => true,
+        }
+    }
+
+    /// Each number as a subscriber to goals for the player's team, with this
+    /// subscription's templates & locale
+    pub fn subscribers(&self) -> Vec<Subscriber> {
+        self.numbers
+            .iter()
+            .map(|number| Subscriber {
+                number: number.clone(),
+                preferences: vec![Preferences {
+                    teams: vec![self.team_id],
+                    events: vec![EventKind::GoalsFor],
+                    only_if_win: false,
+                }],
+                timezone: None,
+                quiet_hours: None,
+                templates: self.templates.clone(),
+                locale: self.locale,
+                highlight_mode: HighlightMode::default(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PlayerPoint {
+    Goal,
+    Assist,
+}
+
+impl PlayerPoint {
+    fn name(self, locale: Locale) -> &'static str {
+        match (locale, self) {
+            (Locale::En, PlayerPoint::Goal) => "goal",
+            (Locale::En, PlayerPoint::Assist) => "assist",
+            (Locale::Fr, PlayerPoint::Goal) => "but",
+            (Locale::Fr, PlayerPoint::Assist) => "passe",
+        }
+    }
+
+    /// Event name recorded in notification history
+    fn event_name(self) -> &'static str {
+        match self {
+            PlayerPoint::Goal => "player_goal",
+            PlayerPoint::Assist => "player_assist",
+        }
+    }
+}
+
+impl std::fmt::Display for PlayerPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name(Locale::En))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct Goal {
     event_id: u32,
     team_id: u32,
     description: String,
     ordinal_num: String,
     period_time: NaiveTime,
+    /// Not saved with game state, highlights notified are tracked by id
+    #[serde(skip)]
     highlight: Option<GameContentMilestoneItemHighlight>,
+    scorer_id: Option<u32>,
+    assist_ids: Vec<u32>,
+    /// Whether the scorer & assists have been found in the live feed
+    #[serde(default)]
+    players_resolved: bool,
+    /// Goal's number in the game, set when it's first notified so it doesn't
+    /// change if an earlier goal is overturned
+    #[serde(default)]
+    number: Option<usize>,
+}
+
+impl Goal {
+    /// Whether a goal with the same event id was changed after review, such as
+    /// a new scorer, changed assists or the goal credited to the other team.
+    /// Assists are only compared once both goals have them from the live feed.
+    fn scoring_changed(&self, other: &Goal) -> bool {
+        self.team_id != other.team_id
+            || self.scorer_id != other.scorer_id
+            || (self.players_resolved
+                && other.players_resolved
+                && self.assist_ids != other.assist_ids)
+    }
+
+    fn player_point(&self, player_id: u32) -> Option<PlayerPoint> {
+        if self.scorer_id == Some(player_id) {
+            Some(PlayerPoint::Goal)
+        } else if self.assist_ids.contains(&player_id) {
+            Some(PlayerPoint::Assist)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_adds_and_deducts_goals() {
+        let mut score = GameScore::new();
+        GameScore::add(&mut score.home, &mut score.home_correction);
+        GameScore::add(&mut score.home, &mut score.home_correction);
+        GameScore::add(&mut score.away, &mut score.away_correction);
+        assert_eq!((score.home, score.away), (2, 1));
+
+        GameScore::deduct(&mut score.home, &mut score.home_correction);
+        GameScore::deduct(&mut score.away, &mut score.away_correction);
+        GameScore::deduct(&mut score.away, &mut score.away_correction);
+        assert_eq!((score.home, score.away), (1, 0));
+    }
+
+    #[test]
+    fn correction_absorbs_late_milestones() {
+        let mut score = GameScore::new();
+        GameScore::add(&mut score.home, &mut score.home_correction);
+
+        // The linescore has a home goal the milestones don't yet
+        score.correct(2, 0);
+        assert_eq!((score.home, score.home_correction), (2, 1));
+
+        // Its milestone turns up, it was already counted
+        GameScore::add(&mut score.home, &mut score.home_correction);
+        assert_eq!((score.home, score.home_correction), (2, 0));
+
+        GameScore::add(&mut score.home, &mut score.home_correction);
+        assert_eq!(score.home, 3);
+    }
+
+    #[test]
+    fn correction_absorbs_late_overturns() {
+        let mut score = GameScore::new();
+        GameScore::add(&mut score.away, &mut score.away_correction);
+        GameScore::add(&mut score.away, &mut score.away_correction);
+
+        // The linescore took away a goal still in the milestones
+        score.correct(0, 1);
+        assert_eq!((score.away, score.away_correction), (1, -1));
+
+        // Its milestone goes away, it was already taken off
+        GameScore::deduct(&mut score.away, &mut score.away_correction);
+        assert_eq!((score.away, score.away_correction), (1, 0));
+
+        GameScore::deduct(&mut score.away, &mut score.away_correction);
+        assert_eq!(score.away, 0);
+    }
 }
@@ -0,0 +1,154 @@
+use serde::Deserialize;
+use stats_api::model::GameContentPlayback;
+
+/// Rendition used when config doesn't list any
+const DEFAULT_RENDITION: &str = "FLASH_1800K_896x504";
+
+/// A highlight clip rendition to prefer, written in config as `highest`,
+/// `lowest`, `mobile`, `hls` or a playback's exact name
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String")]
+pub enum Rendition {
+    /// Highest bitrate mp4
+    Highest,
+    /// Lowest bitrate mp4
+    Lowest,
+    /// Stream sized for phones
+    Mobile,
+    /// Adaptive stream, an .m3u8 playlist
+    Hls,
+    /// A playback by name, ie. FLASH_1800K_896x504
+    Name(String),
+}
+
+impl From<String> for Rendition {
+    fn from(name: String) -> Self {
+        match name.to_lowercase().as_str() {
+            "highest" => Rendition::Highest,
+            "lowest" => Rendition::Lowest,
+            "mobile" => Rendition::Mobile,
+            "hls" => Rendition::Hls,
+            _ => Rendition::Name(name),
+        }
+    }
+}
+
+impl Rendition {
+    fn find<'a>(&self, playbacks: &'a [GameContentPlayback]) -> Option<&'a GameContentPlayback> {
+        let with_bitrate = playbacks
+            .iter()
+            .filter_map(|playback| bitrate(&playback.name).map(|bitrate| (bitrate, playback)));
+
+        match self {
+            Rendition::Highest => with_bitrate
+                .max_by_key(|(bitrate, _)| *bitrate)
+                .map(|(_, playback)| playback),
+            Rendition::Lowest => with_bitrate
+                .min_by_key(|(bitrate, _)| *bitrate)
+                .map(|(_, playback)| playback),
+            Rendition::Mobile => playbacks
+                .iter()
+                .find(|playback| playback.name.contains("MOBILE")),
+            Rendition::Hls => playbacks
+                .iter()
+                .find(|playback| playback.url.ends_with(".m3u8")),
+            Rendition::Name(name) => playbacks.iter().find(|playback| &playback.name == name),
+        }
+    }
+}
+
+pub fn default_renditions() -> Vec<Rendition> {
+    vec![Rendition::Name(DEFAULT_RENDITION.to_owned())]
+}
+
+/// The first playback matching `renditions`, in order of preference. Falls
+/// back to the highest bitrate mp4, then any playback with a url.
+pub fn choose_playback<'a>(
+    renditions: &[Rendition],
+    playbacks: &'a [GameContentPlayback],
+) -> Option<&'a GameContentPlayback> {
+    renditions
+        .iter()
+        .chain(&[Rendition::Highest])
+        .find_map(|rendition| rendition.find(playbacks))
+        .or_else(|| playbacks.iter().find(|playback| !playback.url.is_empty()))
+}
+
+/// Bitrate in kbps from a playback name like FLASH_1800K_896x504
+fn bitrate(name: &str) -> Option<u32> {
+    name.split('_')
+        .filter(|part| part.ends_with('K'))
+        .find_map(|part| part[..part.len() - 1].parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playbacks() -> Vec<GameContentPlayback> {
+        let playback = |name: &str, url: &str| serde_json::json!({ "name": name, "width": "null", "height": "null", "url": url });
+        serde_json::from_value(serde_json::json!([
+            playback("HTTP_CLOUD_WIRED_60", "https://nhl.com/clip.m3u8"),
+            playback("FLASH_450K_400x224", "https://nhl.com/clip_450k.mp4"),
+            playback("FLASH_1800K_896x504", "https://nhl.com/clip_1800k.mp4"),
+            playback("FLASH_3000K_1280x720", "https://nhl.com/clip_3000k.mp4"),
+            playback("HTTP_CLOUD_MOBILE", "https://nhl.com/clip_mobile.mp4"),
+        ]))
+        .unwrap()
+    }
+
+    fn chosen(renditions: &[Rendition], playbacks: &[GameContentPlayback]) -> Option<String> {
+        choose_playback(renditions, playbacks).map(|playback| playback.name.clone())
+    }
+
+    #[test]
+    fn renditions_parse_by_keyword_or_name() {
+        assert_eq!(Rendition::from(String::from("HLS")), Rendition::Hls);
+        assert_eq!(
+            Rendition::from(String::from("FLASH_1800K_896x504")),
+            Rendition::Name(String::from("FLASH_1800K_896x504"))
+        );
+    }
+
+    #[test]
+    fn chooses_first_matching_rendition() {
+        let playbacks = playbacks();
+        let chosen = |renditions: &[Rendition]| chosen(renditions, &playbacks);
+        assert_eq!(
+            chosen(&default_renditions()).unwrap(),
+            "FLASH_1800K_896x504"
+        );
+        assert_eq!(chosen(&[Rendition::Lowest]).unwrap(), "FLASH_450K_400x224");
+        assert_eq!(chosen(&[Rendition::Mobile]).unwrap(), "HTTP_CLOUD_MOBILE");
+        assert_eq!(chosen(&[Rendition::Hls]).unwrap(), "HTTP_CLOUD_WIRED_60");
+        assert_eq!(
+            chosen(&[
+                Rendition::Name(String::from("FLASH_9000K")),
+                Rendition::Lowest
+            ])
+            .unwrap(),
+            "FLASH_450K_400x224"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_highest_then_any_url() {
+        let playbacks = playbacks();
+        let missing = [Rendition::Name(String::from("FLASH_9000K"))];
+        assert_eq!(
+            chosen(&missing, &playbacks).unwrap(),
+            "FLASH_3000K_1280x720"
+        );
+
+        let without_bitrate: Vec<GameContentPlayback> = playbacks
+            .into_iter()
+            .filter(|playback| bitrate(&playback.name).is_none())
+            .collect();
+        assert_eq!(
+            chosen(&missing, &without_bitrate).unwrap(),
+            "HTTP_CLOUD_WIRED_60"
+        );
+
+        assert_eq!(chosen(&missing, &[]), None);
+    }
+}
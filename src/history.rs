@@ -0,0 +1,219 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use failure::{format_err, Error, ResultExt};
+use rusqlite::{params, types::ToSql, Connection, OpenFlags};
+use std::{fs, path::Path, sync::Mutex};
+
+/// Timestamps are stored as fixed width UTC text so they sort and compare
+/// correctly as strings
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Record of every notification sent, kept in a local SQLite database
+pub struct History {
+    conn: Mutex<Connection>,
+}
+
+impl History {
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context(format_err!("Could not create dir {:?}", dir))?;
+        }
+
+        let conn = Connection::open(path)
+            .context(format_err!("Could not open history database {:?}", path))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notifications (
+                id INTEGER PRIMARY KEY,
+                game_id INTEGER NOT NULL,
+                event TEXT NOT NULL,
+                recipient TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                sid TEXT,
+                status TEXT NOT NULL,
+                sent_at TEXT NOT NULL
+            )",
+            params![],
+        )
+        .context("Could not create history table")?;
+
+        Ok(History {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open an existing database only to read it, while games may be writing
+    pub fn open_read_only(path: &Path) -> Result<Self, Error> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .context(format_err!("Could not open history database {:?}", path))?;
+
+        Ok(History {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn record(&self, notification: &Notification) -> Result<(), Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO notifications (game_id, event, recipient, channel, sid, status, sent_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                notification.game_id as i64,
+                notification.event,
+                notification.recipient,
+                notification.channel,
+                notification.sid,
+                notification.status,
+                notification.sent_at.format(TIMESTAMP_FORMAT).to_string(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Notifications matching every filter that's set, oldest first
+    pub fn query(&self, filter: &HistoryFilter) -> Result<Vec<Notification>, Error> {
+        let mut clauses = vec![];
+        let mut values: Vec<Box<dyn ToSql>> = vec![];
+
+        if let Some(date) = filter.date {
+            // Dates are local, so match everything sent between local midnights
+            let midnight = |date: NaiveDate| {
+                date.and_hms_opt(0, 0, 0)
+                    .and_then(|time| Local.from_local_datetime(&time).earliest())
+            };
+            let start = midnight(date);
+            let end = midnight(date + Duration::days(1));
+            match (start, end) {
+                (Some(start), Some(end)) => {
+                    clauses.push("sent_at >= ? AND sent_at < ?");
+                    values.push(Box::new(
+                        start
+                            .with_timezone(&Utc)
+                            .format(TIMESTAMP_FORMAT)
+                            .to_string(),
+                    ));
+                    values.push(Box::new(
+                        end.with_timezone(&Utc).format(TIMESTAMP_FORMAT).to_string(),
+                    ));
+                }
+                _ => return Err(format_err!("Invalid local date: {}", date)),
+            }
+        }
+        if let Some(game_id) = filter.game_id {
+            clauses.push("game_id = ?");
+            values.push(Box::new(game_id as i64));
+        }
+        if let Some(number) = &filter.number {
+            clauses.push("recipient = ?");
+            values.push(Box::new(number.clone()));
+        }
+
+        let mut sql = String::from(
+            "SELECT game_id, event, recipient, channel, sid, status, sent_at FROM notifications",
+        );
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        sql.push_str(" ORDER BY sent_at, id");
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(values.iter().map(|value| value.as_ref()), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        })?;
+
+        let mut notifications = vec![];
+        for row in rows {
+            let (game_id, event, recipient, channel, sid, status, sent_at) = row?;
+            let sent_at = NaiveDateTime::parse_from_str(&sent_at, TIMESTAMP_FORMAT)
+                .map(|time| Utc.from_utc_datetime(&time))
+                .context(format_err!("Invalid timestamp in history: {}", sent_at))?;
+            notifications.push(Notification {
+                game_id: game_id as u64,
+                event,
+                recipient,
+                channel,
+                sid,
+                status,
+                sent_at,
+            });
+        }
+
+        Ok(notifications)
+    }
+}
+
+pub struct Notification {
+    pub game_id: u64,
+    pub event: String,
+    pub recipient: String,
+    pub channel: String,
+    /// Id the provider assigned the message, if it got that far
+    pub sid: Option<String>,
+    pub status: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+impl Notification {
+    /// Whether the provider took the message, rather than it failing or being
+    /// dropped for quiet hours
+    pub fn delivered(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "queued" | "sent" | "delivered" | "printed"
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    pub date: Option<NaiveDate>,
+    pub game_id: Option<u64>,
+    pub number: Option<String>,
+}
+
+/// Print notifications matching `filter` as a table
+pub fn print_history(path: &Path, filter: &HistoryFilter) -> Result<(), Error> {
+    if !path.exists() {
+        log::info!("No history yet, {:?} is created once a game is run", path);
+        return Ok(());
+    }
+
+    let history = History::open_read_only(path)?;
+    let notifications = history.query(filter)?;
+
+    if notifications.is_empty() {
+        log::info!("No notifications found");
+        return Ok(());
+    }
+
+    println!(
+        "{:<25} {:<12} {:<18} {:<16} {:<7} {:<12} SID",
+        "SENT", "GAME", "EVENT", "RECIPIENT", "CHANNEL", "STATUS"
+    );
+    for notification in notifications {
+        println!(
+            "{:<25} {:<12} {:<18} {:<16} {:<7} {:<12} {}",
+            notification
+                .sent_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %I:%M:%S %p"),
+            notification.game_id,
+            notification.event,
+            notification.recipient,
+            notification.channel,
+            notification.status,
+            notification.sid.as_deref().unwrap_or("-"),
+        );
+    }
+
+    Ok(())
+}
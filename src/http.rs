@@ -0,0 +1,60 @@
+use async_std::{io, net::TcpStream, prelude::*};
+use failure::{bail, Error};
+use std::time::Duration;
+
+/// How long a client has to send its request before the connection is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Path of a GET request, ignoring any query string
+pub async fn read_path(stream: &mut TcpStream) -> Result<String, Error> {
+    let request = io::timeout(READ_TIMEOUT, read_headers(stream)).await?;
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => Ok(target.split('?').next().unwrap_or("/").to_owned()),
+        _ => bail!("Only GET requests are supported"),
+    }
+}
+
+pub fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Request line and headers, up to the blank line after them
+async fn read_headers(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut request = vec![];
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > 16 * 1024 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Request headers too long",
+            ));
+        }
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+    Ok(request)
+}
+
+/// Redirect to `location`
+pub fn redirect(location: &str) -> String {
+    format!(
+        "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        location
+    )
+}
@@ -0,0 +1,309 @@
+use crate::{config::AppConfig, game::games_between, http, source::StatsSource};
+use async_std::{
+    net::{TcpListener, TcpStream},
+    prelude::*,
+    task,
+};
+use chrono::{DateTime, Duration, Local, Utc};
+use failure::{format_err, Error, ResultExt};
+use log::{info, warn};
+use stats_api::model::{ScheduleGame, Team};
+use std::{
+    cell::RefCell,
+    fs,
+    net::SocketAddr,
+    path::PathBuf,
+    rc::Rc,
+    time::{self, Instant},
+};
+
+/// Games don't have an end time, so events are given a typical game's length
+const GAME_LENGTH_HOURS: i64 = 3;
+
+/// How long a served calendar is reused before the schedule is fetched again
+const SERVE_CACHE: time::Duration = time::Duration::from_secs(15 * 60);
+
+/// Longest line allowed by RFC 5545, in octets, not counting the line break
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Where the exported calendar goes
+pub enum Export {
+    Print,
+    File(PathBuf),
+    /// Serve the calendar over HTTP so calendar apps can subscribe to it
+    Serve(SocketAddr),
+}
+
+pub async fn export_ics(
+    config: Rc<AppConfig>,
+    source: Rc<dyn StatsSource>,
+    days: u32,
+    export: Export,
+) -> Result<(), Error> {
+    match export {
+        Export::Print => print!("{}", build_calendar(&config, &*source, days).await?),
+        Export::File(path) => {
+            let calendar = build_calendar(&config, &*source, days).await?;
+            fs::write(&path, calendar).context(format_err!("Could not write {:?}", path))?;
+            info!("Calendar written to {:?}", path);
+        }
+        Export::Serve(addr) => serve(config, source, days, addr).await?,
+    }
+
+    Ok(())
+}
+
+/// An RFC 5545 calendar of every game for a subscribed team starting in the
+/// next `days` days
+async fn build_calendar(
+    config: &AppConfig,
+    source: &dyn StatsSource,
+    days: u32,
+) -> Result<String, Error> {
+    let teams = source.get_teams().await?;
+    let subscriptions = config.subscriptions_as_hashmap(&teams)?;
+
+    let now = Local::now();
+    let games = games_between(source, now, now + Duration::days(i64::from(days))).await?;
+
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//tarkah//nhl-notifier//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+        String::from("X-WR-CALNAME:NHL Games"),
+    ];
+
+    let stamp = Utc::now();
+    for game in games {
+        let is_subscribed = subscriptions.contains_key(&game.teams.home.detail.id)
+            || subscriptions.contains_key(&game.teams.away.detail.id);
+        if !is_subscribed {
+            continue;
+        }
+
+        let description = preview_subhead(source, game.game_pk).await;
+        lines.extend(event_lines(&game, &teams, description, stamp));
+    }
+
+    lines.push(String::from("END:VCALENDAR"));
+
+    let mut calendar = String::new();
+    for line in lines {
+        calendar.push_str(&fold(&line));
+        calendar.push_str("\r\n");
+    }
+
+    Ok(calendar)
+}
+
+fn event_lines(
+    game: &ScheduleGame,
+    teams: &[Team],
+    description: Option<String>,
+    stamp: DateTime<Utc>,
+) -> Vec<String> {
+    let home = team_name(
+        teams,
+        game.teams.home.detail.id,
+        &game.teams.home.detail.name,
+    );
+    let away = team_name(
+        teams,
+        game.teams.away.detail.id,
+        &game.teams.away.detail.name,
+    );
+
+    let mut lines = vec![
+        String::from("BEGIN:VEVENT"),
+        format!("UID:{}@nhl-notifier", game.game_pk),
+        format!("DTSTAMP:{}", utc_time(stamp)),
+        format!("DTSTART:{}", utc_time(game.date)),
+        format!(
+            "DTEND:{}",
+            utc_time(game.date + Duration::hours(GAME_LENGTH_HOURS))
+        ),
+        format!("SUMMARY:{}", escape(&format!("{} @ {}", away, home))),
+        format!("LOCATION:{}", escape(&game.venue.name)),
+    ];
+    if let Some(description) = description {
+        lines.push(format!("DESCRIPTION:{}", escape(&description)));
+    }
+    lines.push(String::from("END:VEVENT"));
+
+    lines
+}
+
+/// The preview article's subhead, once the preview has been published
+async fn preview_subhead(source: &dyn StatsSource, game_id: u64) -> Option<String> {
+    match source.get_game_content(game_id).await {
+        Ok(content) => content
+            .editorial
+            .preview
+            .items
+            .and_then(|items| items.into_iter().next())
+            .map(|preview| preview.subhead)
+            .filter(|subhead| !subhead.is_empty()),
+        Err(e) => {
+            warn!("Could not get preview for game {}: {}", game_id, e);
+            None
+        }
+    }
+}
+
+/// Calendar shared by the connections `serve` handles, rebuilt once it's
+/// older than `SERVE_CACHE`
+struct CachedCalendar {
+    config: Rc<AppConfig>,
+    source: Rc<dyn StatsSource>,
+    days: u32,
+    cached: RefCell<Option<(Instant, String)>>,
+}
+
+impl CachedCalendar {
+    async fn get(&self) -> Option<String> {
+        let is_stale = self
+            .cached
+            .borrow()
+            .as_ref()
+            .map(|(built, _)| built.elapsed() >= SERVE_CACHE)
+            .unwrap_or(true);
+        if is_stale {
+            match build_calendar(&self.config, &*self.source, self.days).await {
+                Ok(calendar) => *self.cached.borrow_mut() = Some((Instant::now(), calendar)),
+                Err(e) => warn!("Could not build calendar: {}", e),
+            }
+        }
+
+        self.cached
+            .borrow()
+            .as_ref()
+            .map(|(_, calendar)| calendar.clone())
+    }
+}
+
+/// Serve the calendar at `/` and `/calendar.ics`, handling each connection in
+/// its own task
+async fn serve(
+    config: Rc<AppConfig>,
+    source: Rc<dyn StatsSource>,
+    days: u32,
+    addr: SocketAddr,
+) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .context(format_err!("Could not listen on {}", addr))?;
+    info!("Serving calendar at http://{}/calendar.ics", addr);
+
+    let calendar = Rc::new(CachedCalendar {
+        config,
+        source,
+        days,
+        cached: RefCell::new(None),
+    });
+    let mut incoming = listener.incoming();
+    while let Some(stream) = incoming.next().await {
+        match stream {
+            Ok(stream) => {
+                task::spawn_local(respond(calendar.clone(), stream));
+            }
+            Err(e) => warn!("Could not accept connection: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn respond(calendar: Rc<CachedCalendar>, mut stream: TcpStream) {
+    let response = match http::read_path(&mut stream).await {
+        Ok(ref path) if path == "/" || path == "/calendar.ics" => match calendar.get().await {
+            Some(calendar) => http::response("200 OK", "text/calendar; charset=utf-8", &calendar),
+            None => http::response("503 Service Unavailable", "text/plain", ""),
+        },
+        Ok(_) => http::response("404 Not Found", "text/plain", ""),
+        Err(e) => {
+            warn!("Bad request: {}", e);
+            http::response("400 Bad Request", "text/plain", "")
+        }
+    };
+
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        warn!("Could not send response: {}", e);
+    }
+}
+
+fn team_name(teams: &[Team], team_id: u32, fallback: &str) -> String {
+    teams
+        .iter()
+        .find(|team| team.id == team_id)
+        .map(|team| team.name.clone())
+        .unwrap_or_else(|| fallback.to_owned())
+}
+
+fn utc_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Split a content line so no line is longer than 75 octets, continuing each
+/// with a leading space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_len = 0;
+    for c in line.chars() {
+        if line_len + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_len = 1;
+        }
+        folded.push(c);
+        line_len += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(escape("Devils @ Knights"), "Devils @ Knights");
+        assert_eq!(
+            escape("Home; away, \\ more\r\nnext\nlast"),
+            r"Home\; away\, \\ more\nnext\nlast"
+        );
+    }
+
+    #[test]
+    fn short_lines_are_not_folded() {
+        let line = "a".repeat(MAX_LINE_OCTETS);
+        assert_eq!(fold(&line), line);
+    }
+
+    #[test]
+    fn long_lines_fold_at_75_octets() {
+        let folded = fold(&"a".repeat(200));
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), "a".repeat(200));
+    }
+
+    #[test]
+    fn folding_keeps_characters_whole() {
+        // 74 octets then a 2 octet character that doesn't fit on the line
+        let line = format!("{}é", "a".repeat(74));
+        assert_eq!(fold(&line), format!("{}\r\n é", "a".repeat(74)));
+    }
+}
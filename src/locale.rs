@@ -0,0 +1,74 @@
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use serde::Deserialize;
+
+const FRENCH_WEEKDAYS: [&str; 7] = ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."];
+
+const FRENCH_MONTHS: [&str; 12] = [
+    "janv.", "févr.", "mars", "avr.", "mai", "juin", "juill.", "août", "sept.", "oct.", "nov.",
+    "déc.",
+];
+
+/// Language messages are sent in
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    /// Language code the NHL api uses, ie. for broadcasts
+    pub fn language(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+        }
+    }
+
+    /// Period as the NHL api's ordinal, ie. "1st" or "OT"
+    pub fn period(self, ordinal: &str) -> String {
+        match self {
+            Locale::En => ordinal.to_owned(),
+            Locale::Fr => match ordinal {
+                "1st" => String::from("1re"),
+                "SO" => String::from("TB"),
+                "OT" => String::from("Prol."),
+                _ => {
+                    // "2nd", "3rd" & "4th" or "2OT" for later overtimes
+                    let number: String = ordinal.chars().take_while(char::is_ascii_digit).collect();
+                    if number.is_empty() {
+                        ordinal.to_owned()
+                    } else if ordinal.ends_with("OT") {
+                        format!("{}e prol.", number)
+                    } else {
+                        format!("{}e", number)
+                    }
+                }
+            },
+        }
+    }
+
+    /// Time of day a game starts
+    pub fn start_time(self, time: NaiveDateTime) -> String {
+        match self {
+            Locale::En => time.format("%I:%M:%S %p").to_string(),
+            Locale::Fr => time.format("%-H h %M").to_string(),
+        }
+    }
+
+    /// Day and time a game starts, ie. "Mon Dec 2 07:00 PM"
+    pub fn game_day(self, time: NaiveDateTime) -> String {
+        match self {
+            Locale::En => time.format("%a %b %-d %I:%M %p").to_string(),
+            Locale::Fr => format!(
+                "{} {} {} {} h {:02}",
+                FRENCH_WEEKDAYS[time.weekday().num_days_from_monday() as usize],
+                time.day(),
+                FRENCH_MONTHS[time.month0() as usize],
+                time.hour(),
+                time.minute()
+            ),
+        }
+    }
+}
@@ -1,12 +1,28 @@
 use async_std::task;
 use env_logger::Env;
-use failure::Error;
+use failure::{bail, Error};
+use futures::future;
 use log::error;
-use std::process;
+use std::{fs, process, rc::Rc};
 
 mod cli;
 mod config;
+mod daemon;
+mod digest;
 mod game;
+mod highlight;
+mod history;
+mod http;
+mod ics;
+mod locale;
+mod notifier;
+mod schedule;
+mod shortlink;
+mod simulate;
+mod source;
+mod state;
+mod template;
+mod test_notify;
 
 fn main() -> Result<(), Error> {
     task::block_on(async {
@@ -25,9 +41,63 @@ async fn run() -> Result<(), Error> {
     let result = cli::parse_opts()?;
     match result {
         cli::CliStatus::Exit => process::exit(0),
-        cli::CliStatus::Continue(config) => {
+        cli::CliStatus::Run(config, source) => {
             log::debug!("Config is: {:?}", config);
-            game::run_todays_games(&config).await?;
+            let notifier = Rc::new(notifier::TwilioNotifier::new(&config.twilio));
+            game::run_todays_games(&config, source, notifier).await?;
+        }
+        cli::CliStatus::DryRun(mut config, source) => {
+            log::debug!("Config is: {:?}", config);
+            log::info!("Dry run, notifications will be printed instead of sent");
+
+            // A real run later on shouldn't think these were already sent
+            let scratch_dir = config.use_scratch_dir("dry-run");
+            let notifier = Rc::new(notifier::ConsoleNotifier);
+            let result = game::run_todays_games(&config, source, notifier).await;
+            let _ = fs::remove_dir_all(scratch_dir);
+            result?;
+        }
+        cli::CliStatus::Daemon(config) => {
+            log::debug!("Config is: {:?}", config);
+            let notifier = Rc::new(notifier::TwilioNotifier::new(&config.twilio));
+            let daemon = daemon::run_daemon(&config, Rc::new(source::LiveSource::new()), notifier);
+
+            // Short links are served for as long as the daemon runs
+            match config.short_links.as_ref() {
+                Some(short_links) => {
+                    let store =
+                        shortlink::LinkStore::open(&config.history_db, &short_links.base_url)?;
+                    let serve_links = shortlink::serve_links(Rc::new(store), short_links.listen);
+                    future::try_join(daemon, serve_links).await?;
+                }
+                None => daemon.await?,
+            }
+        }
+        cli::CliStatus::ExportIcs(config, days, export) => {
+            let source = Rc::new(source::LiveSource::new());
+            ics::export_ics(Rc::new(config), source, days, export).await?;
+        }
+        cli::CliStatus::History(config, filter) => {
+            history::print_history(&config.history_db, &filter)?;
+        }
+        cli::CliStatus::Schedule(config, days, format) => {
+            let source = source::LiveSource::new();
+            schedule::print_schedule(&config, &source, days, format).await?;
+        }
+        cli::CliStatus::ServeLinks(config) => match config.short_links.as_ref() {
+            Some(short_links) => {
+                let store = shortlink::LinkStore::open(&config.history_db, &short_links.base_url)?;
+                shortlink::serve_links(Rc::new(store), short_links.listen).await?;
+            }
+            None => bail!("short_links must be set in config to serve short links"),
+        },
+        cli::CliStatus::Simulate(config, simulation) => {
+            simulate::run_simulation(config, simulation).await?;
+        }
+        cli::CliStatus::TestNotify(config, recipients) => {
+            let source = source::LiveSource::new();
+            let notifier = notifier::TwilioNotifier::new(&config.twilio);
+            test_notify::send_test_notifications(&config, &source, &notifier, recipients).await?;
         }
     }
 
@@ -0,0 +1,86 @@
+use crate::config::TwilioConfig;
+use failure::Error;
+use futures::future::{self, FutureExt, LocalBoxFuture};
+
+/// Where notifications are delivered
+pub trait Notifier {
+    /// Channel name recorded in notification history
+    fn channel(&self) -> &'static str;
+
+    fn send<'a>(
+        &'a self,
+        to: &'a str,
+        message: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Delivery, Error>>;
+}
+
+/// Outcome of sending a single message
+pub struct Delivery {
+    /// Id the provider assigned the message, if it has one
+    pub sid: Option<String>,
+    pub status: String,
+    pub delivered: bool,
+}
+
+/// Sends SMS through Twilio
+pub struct TwilioNotifier {
+    client: twilio::Client,
+    from: String,
+}
+
+impl TwilioNotifier {
+    pub fn new(config: &TwilioConfig) -> Self {
+        TwilioNotifier {
+            client: twilio::Client::new(config.account_sid.clone(), config.auth_token.clone()),
+            from: config.from.clone(),
+        }
+    }
+}
+
+impl Notifier for TwilioNotifier {
+    fn channel(&self) -> &'static str {
+        "sms"
+    }
+
+    fn send<'a>(
+        &'a self,
+        to: &'a str,
+        message: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Delivery, Error>> {
+        async move {
+            let response = self.client.send_message(&self.from, to, message).await?;
+            let delivered = response.status == "sent" || response.status == "queued";
+
+            Ok(Delivery {
+                sid: response.sid,
+                status: response.status,
+                delivered,
+            })
+        }
+        .boxed_local()
+    }
+}
+
+/// Prints each message to stdout instead of sending it
+pub struct ConsoleNotifier;
+
+impl Notifier for ConsoleNotifier {
+    fn channel(&self) -> &'static str {
+        "console"
+    }
+
+    fn send<'a>(
+        &'a self,
+        to: &'a str,
+        message: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Delivery, Error>> {
+        println!("----- To: {} -----\n{}\n", to, message);
+
+        future::ready(Ok(Delivery {
+            sid: None,
+            status: String::from("printed"),
+            delivered: true,
+        }))
+        .boxed_local()
+    }
+}
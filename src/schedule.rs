@@ -0,0 +1,164 @@
+use crate::{
+    config::{AppConfig, Subscriber},
+    game::{games_between, resolve_player_subscriptions},
+    source::StatsSource,
+};
+use chrono::{DateTime, Duration, Local};
+use failure::{bail, Error};
+use serde::Serialize;
+use stats_api::model::Team;
+use std::str::FromStr;
+
+/// How the schedule is printed
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            _ => bail!("Unknown format '{}', expected 'table' or 'json'", s),
+        }
+    }
+}
+
+/// An upcoming game for a subscribed team
+#[derive(Serialize)]
+struct UpcomingGame {
+    game_id: u64,
+    start: DateTime<Local>,
+    home: String,
+    away: String,
+    venue: String,
+    subscribed: Vec<SubscribedTeam>,
+    /// Players followed in the game
+    players: Vec<String>,
+    /// Numbers that would be notified, by team or player subscriptions
+    recipients: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct SubscribedTeam {
+    team: String,
+    home: bool,
+}
+
+/// Print games for subscribed teams starting in the next `days` days
+pub async fn print_schedule(
+    config: &AppConfig,
+    source: &dyn StatsSource,
+    days: u32,
+    format: Format,
+) -> Result<(), Error> {
+    let teams = source.get_teams().await?;
+    let subscriptions = config.subscriptions_as_hashmap(&teams)?;
+    let player_subscriptions = resolve_player_subscriptions(config, source).await;
+
+    let now = Local::now();
+    let games = games_between(source, now, now + Duration::days(i64::from(days))).await?;
+
+    let upcoming: Vec<UpcomingGame> = games
+        .into_iter()
+        .filter_map(|game| {
+            let home = &game.teams.home.detail;
+            let away = &game.teams.away.detail;
+            let home_name = abbreviation(&teams, home.id, &home.name);
+            let away_name = abbreviation(&teams, away.id, &away.name);
+
+            let mut subscribed = vec![];
+            let mut subscribers = vec![];
+            for (team_id, name, is_home) in
+                &[(home.id, &home_name, true), (away.id, &away_name, false)]
+            {
+                if let Some(subs) = subscriptions.get(team_id) {
+                    subscribed.push(SubscribedTeam {
+                        team: (*name).clone(),
+                        home: *is_home,
+                    });
+                    subscribers.extend(subs.iter().cloned());
+                }
+            }
+
+            let players: Vec<_> = player_subscriptions
+                .iter()
+                .filter(|player| player.team_id == home.id || player.team_id == away.id)
+                .collect();
+
+            if subscribed.is_empty() && players.is_empty() {
+                return None;
+            }
+
+            let mut recipients: Vec<String> = Subscriber::dedup(subscribers)
+                .into_iter()
+                .map(|sub| sub.number)
+                .collect();
+            for number in players.iter().flat_map(|player| player.numbers.iter()) {
+                if !recipients.contains(number) {
+                    recipients.push(number.clone());
+                }
+            }
+
+            Some(UpcomingGame {
+                game_id: game.game_pk,
+                start: game.date.with_timezone(&Local),
+                home: home_name,
+                away: away_name,
+                venue: game.venue.name,
+                subscribed,
+                players: players.iter().map(|player| player.name.clone()).collect(),
+                recipients,
+            })
+        })
+        .collect();
+
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&upcoming)?),
+        Format::Table => print_table(&upcoming),
+    }
+
+    Ok(())
+}
+
+fn print_table(games: &[UpcomingGame]) {
+    if games.is_empty() {
+        println!("No upcoming games for subscribed teams");
+        return;
+    }
+
+    println!(
+        "{:<12} {:<18} {:<11} {:<16} RECIPIENTS",
+        "GAME", "START", "MATCHUP", "SUBSCRIBED"
+    );
+    for game in games {
+        let subscribed: Vec<String> = game
+            .subscribed
+            .iter()
+            .map(|sub| format!("{} ({})", sub.team, if sub.home { "H" } else { "A" }))
+            .chain(game.players.iter().cloned())
+            .collect();
+
+        println!(
+            "{:<12} {:<18} {:<11} {:<16} {}",
+            game.game_id,
+            game.start.format("%a %b %e %l:%M%P").to_string(),
+            format!("{} @ {}", game.away, game.home),
+            subscribed.join(", "),
+            game.recipients.join(", ")
+        );
+    }
+}
+
+/// Team abbreviation, falling back to the schedule's name for the team
+fn abbreviation(teams: &[Team], team_id: u32, fallback: &str) -> String {
+    teams
+        .iter()
+        .find(|team| team.id == team_id)
+        .map(|team| team.abbreviation.clone())
+        .unwrap_or_else(|| fallback.to_owned())
+}
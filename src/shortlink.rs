@@ -0,0 +1,185 @@
+use crate::http;
+use async_std::{
+    net::{TcpListener, TcpStream},
+    prelude::*,
+    task,
+};
+use failure::{format_err, Error, ResultExt};
+use futures::future::{self, FutureExt, LocalBoxFuture};
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{fs, net::SocketAddr, path::Path, rc::Rc, sync::Mutex};
+
+/// Characters short link ids are written with
+const ID_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Turns a long url into a short one. The built in `LinkStore` is used when
+/// `short_links` is configured, another service can be used by implementing
+/// this.
+pub trait Shortener {
+    fn shorten<'a>(&'a self, url: &'a str) -> LocalBoxFuture<'a, Result<String, Error>>;
+}
+
+/// Short links served as `{base_url}/h/<id>`, kept in the history database
+pub struct LinkStore {
+    conn: Mutex<Connection>,
+    base_url: String,
+}
+
+impl LinkStore {
+    pub fn open(path: &Path, base_url: &str) -> Result<Self, Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context(format_err!("Could not create dir {:?}", dir))?;
+        }
+
+        let conn = Connection::open(path)
+            .context(format_err!("Could not open short link database {:?}", path))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS short_links (
+                id INTEGER PRIMARY KEY,
+                url TEXT NOT NULL UNIQUE
+            )",
+            params![],
+        )
+        .context("Could not create short link table")?;
+
+        Ok(LinkStore {
+            conn: Mutex::new(conn),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+        })
+    }
+
+    /// Id for `url`, the same one each time it's shortened
+    fn id_for(&self, url: &str) -> Result<String, Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO short_links (url) VALUES (?1)",
+            params![url],
+        )?;
+        let id: i64 = conn.query_row(
+            "SELECT id FROM short_links WHERE url = ?1",
+            params![url],
+            |row| row.get(0),
+        )?;
+
+        Ok(encode_id(id as u64))
+    }
+
+    /// Url a short link id points to
+    fn url(&self, id: &str) -> Result<Option<String>, Error> {
+        let id = match decode_id(id) {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let conn = self.conn.lock().unwrap();
+        let url = conn
+            .query_row(
+                "SELECT url FROM short_links WHERE id = ?1",
+                params![id as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(url)
+    }
+}
+
+impl Shortener for LinkStore {
+    fn shorten<'a>(&'a self, url: &'a str) -> LocalBoxFuture<'a, Result<String, Error>> {
+        let short_url = self
+            .id_for(url)
+            .map(|id| format!("{}/h/{}", self.base_url, id));
+        future::ready(short_url).boxed_local()
+    }
+}
+
+/// Redirect `/h/<id>` requests to the url the id was made for. Each connection
+/// is handled in its own task, so a slow client doesn't hold up the rest.
+pub async fn serve_links(store: Rc<LinkStore>, addr: SocketAddr) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .context(format_err!("Could not listen on {}", addr))?;
+    info!("Serving short links at http://{}/h/", addr);
+
+    let mut incoming = listener.incoming();
+    while let Some(stream) = incoming.next().await {
+        match stream {
+            Ok(stream) => {
+                task::spawn_local(respond(store.clone(), stream));
+            }
+            Err(e) => warn!("Could not accept connection: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn respond(store: Rc<LinkStore>, mut stream: TcpStream) {
+    let response = match http::read_path(&mut stream).await {
+        Ok(ref path) if path.starts_with("/h/") => match store.url(&path[3..]) {
+            Ok(Some(url)) => http::redirect(&url),
+            Ok(None) => http::response("404 Not Found", "text/plain", ""),
+            Err(e) => {
+                warn!("Could not look up short link: {}", e);
+                http::response("503 Service Unavailable", "text/plain", "")
+            }
+        },
+        Ok(_) => http::response("404 Not Found", "text/plain", ""),
+        Err(e) => {
+            warn!("Bad request: {}", e);
+            http::response("400 Bad Request", "text/plain", "")
+        }
+    };
+
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        warn!("Could not send response: {}", e);
+    }
+}
+
+fn encode_id(mut id: u64) -> String {
+    let mut chars = vec![];
+    loop {
+        chars.push(ID_CHARS[(id % ID_CHARS.len() as u64) as usize]);
+        id /= ID_CHARS.len() as u64;
+        if id == 0 {
+            break;
+        }
+    }
+    chars.reverse();
+    String::from_utf8(chars).unwrap_or_default()
+}
+
+fn decode_id(id: &str) -> Option<u64> {
+    if id.is_empty() {
+        return None;
+    }
+    id.bytes().try_fold(0u64, |value, c| {
+        let digit = ID_CHARS.iter().position(|id_char| *id_char == c)? as u64;
+        value.checked_mul(ID_CHARS.len() as u64)?.checked_add(digit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_round_trip() {
+        for &id in &[0, 1, 61, 62, 3843, 3844, u64::from(u32::MAX), u64::MAX] {
+            assert_eq!(decode_id(&encode_id(id)), Some(id), "id {}", id);
+        }
+        assert_eq!(encode_id(0), "0");
+        assert_eq!(encode_id(61), "Z");
+        assert_eq!(encode_id(62), "10");
+    }
+
+    #[test]
+    fn invalid_ids_are_rejected() {
+        assert_eq!(decode_id(""), None);
+        assert_eq!(decode_id("ab-c"), None);
+        assert_eq!(decode_id("favicon.ico"), None);
+        // One more digit than u64::MAX needs
+        assert_eq!(decode_id(&format!("{}0", encode_id(u64::MAX))), None);
+    }
+}
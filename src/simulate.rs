@@ -0,0 +1,866 @@
+use crate::{
+    config::{AppConfig, TeamRef},
+    game,
+    notifier::ConsoleNotifier,
+    source::{Entry, Replayer, StatsSource},
+};
+use chrono::{DateTime, Duration, Local, Utc};
+use failure::{bail, Error, ResultExt};
+use serde_json::{json, Value};
+use stats_api::model::Team;
+use std::{fs, rc::Rc};
+
+/// Game id used for the simulated game
+const GAME_ID: u64 = 2019029999;
+
+/// Minutes after the simulation starts that the puck drops
+const PUCK_DROP: i64 = 20;
+
+/// Teams as of 2019-20: id, name, abbreviation, team name, location, division
+#[rustfmt::skip]
+const TEAMS: [(u32, &str, &str, &str, &str, &str); 31] = [
+    (1, "New Jersey Devils", "NJD", "Devils", "New Jersey", "Metropolitan"),
+    (2, "New York Islanders", "NYI", "Islanders", "New York", "Metropolitan"),
+    (3, "New York Rangers", "NYR", "Rangers", "New York", "Metropolitan"),
+    (4, "Philadelphia Flyers", "PHI", "Flyers", "Philadelphia", "Metropolitan"),
+    (5, "Pittsburgh Penguins", "PIT", "Penguins", "Pittsburgh", "Metropolitan"),
+    (6, "Boston Bruins", "BOS", "Bruins", "Boston", "Atlantic"),
+    (7, "Buffalo Sabres", "BUF", "Sabres", "Buffalo", "Atlantic"),
+    (8, "Montréal Canadiens", "MTL", "Canadiens", "Montréal", "Atlantic"),
+    (9, "Ottawa Senators", "OTT", "Senators", "Ottawa", "Atlantic"),
+    (10, "Toronto Maple Leafs", "TOR", "Maple Leafs", "Toronto", "Atlantic"),
+    (12, "Carolina Hurricanes", "CAR", "Hurricanes", "Carolina", "Metropolitan"),
+    (13, "Florida Panthers", "FLA", "Panthers", "Florida", "Atlantic"),
+    (14, "Tampa Bay Lightning", "TBL", "Lightning", "Tampa Bay", "Atlantic"),
+    (15, "Washington Capitals", "WSH", "Capitals", "Washington", "Metropolitan"),
+    (16, "Chicago Blackhawks", "CHI", "Blackhawks", "Chicago", "Central"),
+    (17, "Detroit Red Wings", "DET", "Red Wings", "Detroit", "Atlantic"),
+    (18, "Nashville Predators", "NSH", "Predators", "Nashville", "Central"),
+    (19, "St. Louis Blues", "STL", "Blues", "St. Louis", "Central"),
+    (20, "Calgary Flames", "CGY", "Flames", "Calgary", "Pacific"),
+    (21, "Colorado Avalanche", "COL", "Avalanche", "Colorado", "Central"),
+    (22, "Edmonton Oilers", "EDM", "Oilers", "Edmonton", "Pacific"),
+    (23, "Vancouver Canucks", "VAN", "Canucks", "Vancouver", "Pacific"),
+    (24, "Anaheim Ducks", "ANA", "Ducks", "Anaheim", "Pacific"),
+    (25, "Dallas Stars", "DAL", "Stars", "Dallas", "Central"),
+    (26, "Los Angeles Kings", "LAK", "Kings", "Los Angeles", "Pacific"),
+    (28, "San Jose Sharks", "SJS", "Sharks", "San Jose", "Pacific"),
+    (29, "Columbus Blue Jackets", "CBJ", "Blue Jackets", "Columbus", "Metropolitan"),
+    (30, "Minnesota Wild", "MIN", "Wild", "Minnesota", "Central"),
+    (52, "Winnipeg Jets", "WPG", "Jets", "Winnipeg", "Central"),
+    (53, "Arizona Coyotes", "ARI", "Coyotes", "Arizona", "Central"),
+    (54, "Vegas Golden Knights", "VGK", "Golden Knights", "Vegas", "Pacific"),
+];
+
+/// Players on each side: id, name. Last is the goalie.
+const HOME_PLAYERS: [(u32, &str); 4] = [
+    (8_499_001, "Alex Morgan"),
+    (8_499_002, "Ben Walker"),
+    (8_499_003, "Chris Hayes"),
+    (8_499_030, "Dan Price"),
+];
+const AWAY_PLAYERS: [(u32, &str); 4] = [
+    (8_499_101, "Evan Brooks"),
+    (8_499_102, "Frank Cole"),
+    (8_499_103, "Gus Turner"),
+    (8_499_130, "Hank Lund"),
+];
+
+/// Minutes after the simulation starts that each period starts
+const PERIOD_STARTS: [i64; 3] = [PUCK_DROP, 47, 70];
+
+/// Minutes after the simulation starts that the game ends and the recap is
+/// published
+const GAME_END: i64 = 85;
+const RECAP_PUBLISHED: i64 = 88;
+
+struct SimGoal {
+    /// Minutes after the simulation starts that the goal is scored
+    at: i64,
+    /// Minutes after the simulation starts that the goal is overturned
+    overturned_at: Option<i64>,
+    /// Minutes after the simulation starts that the highlight is published
+    highlight_at: Option<i64>,
+    home: bool,
+    period: u8,
+    /// Time elapsed in the period, as the milestones have it
+    period_time: &'static str,
+    scorer: usize,
+    assists: &'static [usize],
+    shot: &'static str,
+}
+
+const GOALS: [SimGoal; 5] = [
+    SimGoal {
+        at: 27,
+        overturned_at: None,
+        highlight_at: Some(30),
+        home: true,
+        period: 1,
+        period_time: "06:12",
+        scorer: 0,
+        assists: &[1, 2],
+        shot: "Wrist Shot",
+    },
+    SimGoal {
+        at: 41,
+        overturned_at: None,
+        highlight_at: Some(41),
+        home: false,
+        period: 1,
+        period_time: "15:40",
+        scorer: 0,
+        assists: &[1],
+        shot: "Snap Shot",
+    },
+    SimGoal {
+        at: 52,
+        overturned_at: Some(56),
+        highlight_at: None,
+        home: true,
+        period: 2,
+        period_time: "03:05",
+        scorer: 1,
+        assists: &[0],
+        shot: "Tip-In",
+    },
+    SimGoal {
+        at: 63,
+        overturned_at: None,
+        highlight_at: Some(66),
+        home: true,
+        period: 2,
+        period_time: "10:20",
+        scorer: 2,
+        assists: &[],
+        shot: "Backhand",
+    },
+    SimGoal {
+        at: 78,
+        overturned_at: None,
+        highlight_at: Some(80),
+        home: true,
+        period: 3,
+        period_time: "18:30",
+        scorer: 0,
+        assists: &[1],
+        shot: "Wrist Shot",
+    },
+];
+
+pub struct Simulation {
+    /// Defaults to the first team subscribed to
+    pub home: Option<TeamRef>,
+    pub away: Option<TeamRef>,
+    pub speed: f64,
+}
+
+/// Run a made up game through the normal game loop, printing every
+/// notification instead of sending it: a preview, puck drop, goals, an
+/// overturned goal, highlights, the final and the recap.
+pub async fn run_simulation(mut config: AppConfig, simulation: Simulation) -> Result<(), Error> {
+    let teams: Vec<Team> = teams_json()
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<Result<_, _>>()
+        .context("Could not build simulated teams")?;
+
+    let home = match simulation.home {
+        Some(home) => home,
+        None => config
+            .subscriptions
+            .iter()
+            .filter_map(|sub| sub.team.clone())
+            .next()
+            .unwrap_or_else(|| TeamRef::Name(String::from("VGK"))),
+    };
+    let home_id = home.resolve(&teams)?;
+    let away_id = match simulation.away {
+        Some(away) => away.resolve(&teams)?,
+        None => teams
+            .iter()
+            .map(|team| team.id)
+            .find(|id| *id != home_id)
+            .unwrap(),
+    };
+    if home_id == away_id {
+        bail!("Home and away teams must be different");
+    }
+    let home = teams.iter().find(|team| team.id == home_id).unwrap();
+    let away = teams.iter().find(|team| team.id == away_id).unwrap();
+
+    // A previous simulation should never be resumed
+    let scratch_dir = config.use_scratch_dir("simulate");
+
+    // Every notification is printed as it happens, whatever the time of day
+    config.earliest_notification_time = Local::now().time();
+    for sub in config.subscriptions.iter_mut() {
+        sub.quiet_hours = None;
+    }
+
+    let source = Replayer::from_entries(timeline(Utc::now(), home, away), simulation.speed)?;
+    let schedule = source
+        .get_schedule_for(Local::now().naive_local().date())
+        .await?;
+
+    log::info!(
+        "Simulating {} vs. {} at {}x speed...",
+        home.name,
+        away.name,
+        simulation.speed
+    );
+
+    let subscriptions = config.subscriptions_as_hashmap(&teams)?;
+    game::run_games(
+        &config,
+        Rc::new(source),
+        Rc::new(ConsoleNotifier),
+        &subscriptions,
+        schedule.games,
+    )
+    .await;
+
+    let _ = fs::remove_dir_all(&scratch_dir);
+
+    Ok(())
+}
+
+/// Every response the simulated game gives, from `start`
+fn timeline(start: DateTime<Utc>, home: &Team, away: &Team) -> Vec<Entry> {
+    let at = |minutes: i64| start + Duration::minutes(minutes);
+    let entry = |minutes: i64, call: String, response: Value| Entry {
+        time: at(minutes),
+        call,
+        response,
+    };
+
+    let mut entries = vec![
+        entry(0, String::from("teams"), Value::Array(teams_json())),
+        entry(0, format!("team/{}", home.id), team_json(home)),
+        entry(0, format!("team/{}", away.id), team_json(away)),
+        entry(
+            0,
+            format!("schedule/{}", Local::now().naive_local().date()),
+            schedule_json(at(PUCK_DROP), home, away),
+        ),
+        entry(
+            0,
+            format!("broadcasts/{}", GAME_ID),
+            broadcasts_json(home, away),
+        ),
+    ];
+
+    // Player subscriptions look up each player's team
+    for (team, is_home) in [(home, true), (away, false)].iter() {
+        for (id, name) in players(*is_home).iter() {
+            entries.push(entry(
+                0,
+                format!("person/{}", id),
+                json!({ "id": id, "fullName": name, "currentTeam": team_ref_json(team) }),
+            ));
+        }
+    }
+
+    // A snapshot of everything whenever something changes
+    let mut times: Vec<i64> = vec![0, PUCK_DROP, GAME_END, RECAP_PUBLISHED];
+    times.extend(PERIOD_STARTS.iter());
+    for goal in GOALS.iter() {
+        times.push(goal.at);
+        times.extend(goal.overturned_at);
+        times.extend(goal.highlight_at);
+    }
+    times.sort();
+    times.dedup();
+
+    for minutes in times {
+        entries.push(entry(
+            minutes,
+            format!("content/{}", GAME_ID),
+            content_json(minutes, at, home, away),
+        ));
+        entries.push(entry(
+            minutes,
+            format!("linescore/{}", GAME_ID),
+            linescore_json(minutes, home, away),
+        ));
+        entries.push(entry(
+            minutes,
+            format!("feed/{}", GAME_ID),
+            live_feed_json(minutes, home, away),
+        ));
+    }
+
+    entries
+}
+
+fn goals_at(minutes: i64) -> impl Iterator<Item = (usize, &'static SimGoal)> {
+    GOALS.iter().enumerate().filter(move |(_, goal)| {
+        let overturned = goal
+            .overturned_at
+            .is_some_and(|overturned| minutes >= overturned);
+        goal.at <= minutes && !overturned
+    })
+}
+
+fn score_at(minutes: i64) -> (u32, u32) {
+    goals_at(minutes).fold((0, 0), |(home, away), (_, goal)| {
+        if goal.home {
+            (home + 1, away)
+        } else {
+            (home, away + 1)
+        }
+    })
+}
+
+fn period_at(minutes: i64) -> usize {
+    PERIOD_STARTS
+        .iter()
+        .filter(|start| **start <= minutes)
+        .count()
+}
+
+fn ordinal(period: usize) -> &'static str {
+    match period {
+        1 => "1st",
+        2 => "2nd",
+        _ => "3rd",
+    }
+}
+
+fn players(home: bool) -> &'static [(u32, &'static str); 4] {
+    if home {
+        &HOME_PLAYERS
+    } else {
+        &AWAY_PLAYERS
+    }
+}
+
+/// Goal and assist totals so far, as shown in goal descriptions
+fn goal_description(idx: usize, goal: &SimGoal) -> String {
+    let players = players(goal.home);
+    let earlier: Vec<&SimGoal> = GOALS[..=idx]
+        .iter()
+        .filter(|earlier| earlier.home == goal.home && earlier.overturned_at.is_none())
+        .collect();
+    let goals_for = |player: usize| earlier.iter().filter(|g| g.scorer == player).count();
+    let assists_for = |player: usize| {
+        earlier
+            .iter()
+            .filter(|g| g.assists.contains(&player))
+            .count()
+    };
+
+    let assists = if goal.assists.is_empty() {
+        String::from("none")
+    } else {
+        goal.assists
+            .iter()
+            .map(|player| format!("{} ({})", players[*player].1, assists_for(*player).max(1)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    format!(
+        "{} ({}) {}, assists: {}",
+        players[goal.scorer].1,
+        goals_for(goal.scorer).max(1),
+        goal.shot,
+        assists
+    )
+}
+
+pub(crate) fn teams_json() -> Vec<Value> {
+    TEAMS
+        .iter()
+        .map(
+            |(id, name, abbreviation, team_name, location_name, division)| {
+                let (division_id, conference_id, conference) = match *division {
+                    "Metropolitan" => (18, 6, "Eastern"),
+                    "Atlantic" => (17, 6, "Eastern"),
+                    "Central" => (16, 5, "Western"),
+                    _ => (15, 5, "Western"),
+                };
+                json!({
+                    "id": id,
+                    "name": name,
+                    "link": format!("/api/v1/teams/{}", id),
+                    "venue": {
+                        "name": "Simulated Arena",
+                        "link": "/api/v1/venues/null",
+                        "city": location_name,
+                    },
+                    "abbreviation": abbreviation,
+                    "teamName": team_name,
+                    "locationName": location_name,
+                    "firstYearOfPlay": "1917",
+                    "division": {
+                        "id": division_id,
+                        "name": division,
+                        "link": format!("/api/v1/divisions/{}", division_id),
+                    },
+                    "conference": {
+                        "id": conference_id,
+                        "name": conference,
+                        "link": format!("/api/v1/conferences/{}", conference_id),
+                    },
+                    "franchise": {
+                        "franchiseId": id,
+                        "teamName": team_name,
+                        "link": format!("/api/v1/franchises/{}", id),
+                    },
+                    "shortName": location_name,
+                    "officialSiteUrl": "https://www.nhl.com",
+                    "franchiseId": id,
+                    "active": true,
+                })
+            },
+        )
+        .collect()
+}
+
+fn team_json(team: &Team) -> Value {
+    teams_json()
+        .into_iter()
+        .find(|json| json["id"] == team.id)
+        .unwrap_or(Value::Null)
+}
+
+fn team_ref_json(team: &Team) -> Value {
+    json!({
+        "id": team.id,
+        "name": team.name,
+        "link": format!("/api/v1/teams/{}", team.id),
+    })
+}
+
+fn schedule_json(puck_drop: DateTime<Utc>, home: &Team, away: &Team) -> Value {
+    let schedule_team = |team: &Team| {
+        json!({
+            "leagueRecord": { "wins": 0, "losses": 0, "ot": 0, "type": "league" },
+            "score": 0,
+            "team": team_ref_json(team),
+        })
+    };
+
+    json!({
+        "date": puck_drop.with_timezone(&Local).naive_local().date(),
+        "totalItems": 1,
+        "totalEvents": 0,
+        "totalGames": 1,
+        "totalMatches": 0,
+        "games": [{
+            "gamePk": GAME_ID,
+            "link": format!("/api/v1/game/{}/feed/live", GAME_ID),
+            "gameType": "R",
+            "season": "20192020",
+            "gameDate": puck_drop.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            "status": {
+                "abstractGameState": "Preview",
+                "codedGameState": "1",
+                "detailedState": "Scheduled",
+                "statusCode": "1",
+                "startTimeTBD": false,
+            },
+            "teams": {
+                "away": schedule_team(away),
+                "home": schedule_team(home),
+            },
+            "venue": { "name": "Simulated Arena", "link": "/api/v1/venues/null" },
+            "content": { "link": format!("/api/v1/game/{}/content", GAME_ID) },
+        }],
+        "events": [],
+        "matches": [],
+    })
+}
+
+fn broadcasts_json(home: &Team, away: &Team) -> Value {
+    json!([
+        { "name": format!("{} TV", home.team_name), "type": "home", "language": "en" },
+        { "name": format!("{} TV", away.team_name), "type": "away", "language": "en" },
+        { "name": "NHL Network", "type": "national", "language": "en" },
+        { "name": "TVA Sports", "type": "national", "language": "fr" },
+    ])
+}
+
+fn article_json(kind: &str, headline: String, subhead: String) -> Value {
+    json!({
+        "type": "article",
+        "state": "published",
+        "id": format!("simulated-{}", kind),
+        "headline": headline,
+        "subhead": subhead,
+        "seoTitle": headline,
+        "seoDescription": subhead,
+        "slug": format!("simulated-{}", kind),
+        "url": format!("/news/simulated-{}/c-{}", kind, GAME_ID),
+        "tokenData": {},
+        "keywordsDisplay": [],
+        "keywordsAll": [],
+    })
+}
+
+fn content_json(
+    minutes: i64,
+    at: impl Fn(i64) -> DateTime<Utc>,
+    home: &Team,
+    away: &Team,
+) -> Value {
+    let timestamp = |minutes: i64| at(minutes).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let milestone = |kind: &str, minutes: i64, period: usize, extra: Value| {
+        let mut item = json!({
+            "title": kind.replace('_', " "),
+            "description": "",
+            "type": kind,
+            "timeAbsolute": timestamp(minutes),
+            "timeOffset": ((minutes - PUCK_DROP) * 60).to_string(),
+            "period": period.to_string(),
+            "statsEventId": "",
+            "teamId": "",
+            "playerId": "",
+            "periodTime": "00:00",
+            "ordinalNum": ordinal(period),
+        });
+        if let (Some(item), Value::Object(extra)) = (item.as_object_mut(), extra) {
+            item.extend(extra);
+        }
+        item
+    };
+
+    let mut items = vec![];
+    if minutes >= PUCK_DROP {
+        items.push(milestone("BROADCAST_START", PUCK_DROP, 1, Value::Null));
+    }
+    for (idx, start) in PERIOD_STARTS.iter().enumerate() {
+        if minutes >= *start {
+            items.push(milestone("PERIOD_START", *start, idx + 1, Value::Null));
+        }
+    }
+    for (idx, goal) in goals_at(minutes) {
+        let team = if goal.home { home } else { away };
+        let players = players(goal.home);
+        let description = goal_description(idx, goal);
+
+        let mut extra = json!({
+            "description": description,
+            "statsEventId": (100 + idx).to_string(),
+            "teamId": team.id.to_string(),
+            "playerId": players[goal.scorer].0.to_string(),
+            "periodTime": goal.period_time,
+        });
+        if goal
+            .highlight_at
+            .is_some_and(|published| minutes >= published)
+        {
+            let blurb = format!(
+                "{} scores against {}",
+                players[goal.scorer].1,
+                if goal.home { &away.name } else { &home.name }
+            );
+            extra["highlight"] = json!({
+                "type": "video",
+                "id": (70_000 + idx).to_string(),
+                "title": blurb,
+                "blurb": blurb,
+                "description": blurb,
+                "duration": "00:45",
+                "mediaPlaybackId": (70_000 + idx).to_string(),
+                "playbacks": [
+                    {
+                        "name": "HTTP_CLOUD_WIRED_60",
+                        "width": "null",
+                        "height": "null",
+                        "url": format!("https://www.nhl.com/video/simulated-goal-{}.m3u8", idx + 1),
+                    },
+                    {
+                        "name": "FLASH_1800K_896x504",
+                        "width": "896",
+                        "height": "504",
+                        "url": format!("https://www.nhl.com/video/simulated-goal-{}.mp4", idx + 1),
+                    },
+                ],
+            });
+        }
+        items.push(milestone("GOAL", goal.at, goal.period as usize, extra));
+    }
+    if minutes >= GAME_END {
+        items.push(milestone("BROADCAST_END", GAME_END, 3, Value::Null));
+    }
+
+    let preview = article_json(
+        "preview",
+        format!(
+            "{} host {} in simulated game",
+            home.team_name, away.team_name
+        ),
+        format!(
+            "{} look to stay hot at home; {} have won two straight",
+            home.team_name, away.team_name
+        ),
+    );
+    let recap = if minutes >= RECAP_PUBLISHED {
+        let (home_goals, away_goals) = score_at(minutes);
+        vec![article_json(
+            "recap",
+            format!(
+                "{} defeat {} {}-{}",
+                home.team_name, away.team_name, home_goals, away_goals
+            ),
+            format!("{} scores twice for {}", HOME_PLAYERS[0].1, home.team_name),
+        )]
+    } else {
+        vec![]
+    };
+
+    json!({
+        "copyright": "Simulated game, not real NHL data",
+        "link": format!("/api/v1/game/{}/content", GAME_ID),
+        "editorial": {
+            "preview": { "title": "Preview", "topicList": "", "items": [preview] },
+            "articles": { "title": "Articles", "topicList": "", "items": [] },
+            "recap": { "title": "Recap", "topicList": "", "items": recap },
+        },
+        "media": {
+            "epg": [],
+            "milestones": {
+                "title": "Milestones",
+                "streamStart": if minutes >= PUCK_DROP { Value::from(timestamp(PUCK_DROP)) } else { Value::Null },
+                "items": items,
+            },
+        },
+        "highlights": {},
+    })
+}
+
+fn linescore_json(minutes: i64, home: &Team, away: &Team) -> Value {
+    let (home_goals, away_goals) = score_at(minutes);
+    let period = period_at(minutes);
+
+    json!({
+        "currentPeriod": period,
+        "currentPeriodOrdinal": if period > 0 { Value::from(ordinal(period)) } else { Value::Null },
+        "currentPeriodTimeRemaining": if minutes >= GAME_END { "Final" } else { "10:00" },
+        "teams": {
+            "home": { "team": team_ref_json(home), "goals": home_goals, "shotsOnGoal": home_goals * 8 },
+            "away": { "team": team_ref_json(away), "goals": away_goals, "shotsOnGoal": away_goals * 8 + 5 },
+        },
+    })
+}
+
+fn live_feed_json(minutes: i64, home: &Team, away: &Team) -> Value {
+    let person = |(id, name): (u32, &str)| json!({ "id": id, "fullName": name });
+
+    let plays: Vec<Value> = goals_at(minutes)
+        .map(|(idx, goal)| {
+            let players = players(goal.home);
+            let mut play_players = vec![json!({
+                "player": person(players[goal.scorer]),
+                "playerType": "Scorer",
+            })];
+            for assist in goal.assists {
+                play_players.push(json!({
+                    "player": person(players[*assist]),
+                    "playerType": "Assist",
+                }));
+            }
+            json!({
+                "result": { "eventTypeId": "GOAL" },
+                "about": { "eventId": 100 + idx },
+                "players": play_players,
+            })
+        })
+        .collect();
+
+    let (home_goals, away_goals) = score_at(minutes);
+    let boxscore_team =
+        |team: &Team, is_home: bool, goals: u32, shots: u32, against: (u32, u32)| {
+            let goalie = players(is_home)[3];
+            // Snapshots are a minute after whatever they're for, so the
+            // starter has played by the puck drop's
+            let time_on_ice = if minutes < PUCK_DROP {
+                String::from("0:00")
+            } else {
+                format!("{}:00", (minutes - PUCK_DROP + 1).min(GAME_END - PUCK_DROP))
+            };
+            let (shots_against, goals_against) = against;
+            let mut players = serde_json::Map::new();
+            players.insert(
+                format!("ID{}", goalie.0),
+                json!({
+                    "person": person(goalie),
+                    "stats": {
+                        "goalieStats": {
+                            "saves": shots_against - goals_against,
+                            "shots": shots_against,
+                            "timeOnIce": time_on_ice,
+                        },
+                    },
+                }),
+            );
+            json!({
+                "team": team_ref_json(team),
+                "teamStats": {
+                    "teamSkaterStats": {
+                        "goals": goals,
+                        "shots": shots,
+                        "pim": 6,
+                        "powerPlayGoals": 1.0,
+                        "powerPlayOpportunities": 3.0,
+                    },
+                },
+                "players": players,
+                "goalies": [goalie.0],
+            })
+        };
+
+    let home_shots = 31;
+    let away_shots = 27;
+    let decisions = if minutes >= RECAP_PUBLISHED {
+        json!({
+            "winner": person(HOME_PLAYERS[3]),
+            "loser": person(AWAY_PLAYERS[3]),
+            "firstStar": person(HOME_PLAYERS[0]),
+            "secondStar": person(HOME_PLAYERS[3]),
+            "thirdStar": person(AWAY_PLAYERS[0]),
+        })
+    } else {
+        json!({})
+    };
+
+    json!({
+        "gamePk": GAME_ID,
+        "liveData": {
+            "plays": { "allPlays": plays },
+            "linescore": linescore_json(minutes, home, away),
+            "boxscore": {
+                "teams": {
+                    "home": boxscore_team(home, true, home_goals, home_shots, (away_shots, away_goals)),
+                    "away": boxscore_team(away, false, away_goals, away_shots, (home_shots, home_goals)),
+                },
+            },
+            "decisions": decisions,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifier::{Delivery, Notifier};
+    use async_std::task;
+    use futures::future::{self, FutureExt, LocalBoxFuture};
+    use std::{cell::RefCell, path::Path};
+
+    /// Keeps every message instead of sending it
+    #[derive(Default)]
+    struct CapturingNotifier {
+        messages: RefCell<Vec<(String, String)>>,
+    }
+
+    impl Notifier for CapturingNotifier {
+        fn channel(&self) -> &'static str {
+            "test"
+        }
+
+        fn send<'a>(
+            &'a self,
+            to: &'a str,
+            message: &'a str,
+        ) -> LocalBoxFuture<'a, Result<Delivery, Error>> {
+            self.messages
+                .borrow_mut()
+                .push((to.to_owned(), message.to_owned()));
+
+            future::ready(Ok(Delivery {
+                sid: None,
+                status: String::from("captured"),
+                delivered: true,
+            }))
+            .boxed_local()
+        }
+    }
+
+    fn test_config(dir: &Path) -> AppConfig {
+        let path = dir.join("config.yml");
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            &path,
+            "earliest_notification_time: 00:00:00
+subscriptions:
+  - team: VGK
+    events: [goals_for, goals_against, disallowed, final]
+    numbers:
+      - \"+15555555\"
+",
+        )
+        .unwrap();
+
+        let mut config = AppConfig::new(path, String::new(), String::new(), String::new()).unwrap();
+        config.use_scratch_dir("test-replay");
+        config
+    }
+
+    #[test]
+    fn replay_notifies_goals_overturn_and_final() {
+        let dir = std::env::temp_dir().join(format!("nhl-notifier-test-{}", std::process::id()));
+        let config = test_config(&dir);
+
+        let teams: Vec<Team> = teams_json()
+            .into_iter()
+            .map(|json| serde_json::from_value(json).unwrap())
+            .collect();
+        let home = teams
+            .iter()
+            .find(|team| team.abbreviation == "VGK")
+            .unwrap();
+        let away = teams
+            .iter()
+            .find(|team| team.abbreviation == "NJD")
+            .unwrap();
+        let subscriptions = config.subscriptions_as_hashmap(&teams).unwrap();
+
+        let source = Replayer::stepped(timeline(Utc::now(), home, away)).unwrap();
+        let notifier = Rc::new(CapturingNotifier::default());
+
+        let ran = task::block_on(async {
+            let schedule = source
+                .get_schedule_for(Local::now().naive_local().date())
+                .await
+                .unwrap();
+            game::run_games(
+                &config,
+                Rc::new(source),
+                notifier.clone(),
+                &subscriptions,
+                schedule.games,
+            )
+            .await
+        });
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(config.state_dir.parent().unwrap());
+
+        assert_eq!(ran, vec![GAME_ID]);
+
+        let messages: Vec<String> = notifier
+            .messages
+            .borrow()
+            .iter()
+            .map(|(to, message)| {
+                assert_eq!(to, "+15555555");
+                message.clone()
+            })
+            .collect();
+        let count = |text: &str| {
+            messages
+                .iter()
+                .filter(|message| message.contains(text))
+                .count()
+        };
+
+        assert_eq!(count("Golden Knights score"), 4);
+        assert_eq!(count("Devils score"), 1);
+        assert_eq!(count("Golden Knights goal disallowed"), 1);
+        assert_eq!(count("Final score: VGK 3 - NJD 1"), 1);
+        assert!(messages.last().unwrap().starts_with("Golden Knights win"));
+    }
+}
@@ -0,0 +1,410 @@
+use async_std::task;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use failure::{bail, format_err, Error, ResultExt};
+use feed::model::{Broadcast, Linescore, LiveFeed, PersonDetail};
+use futures::future::{self, Future, FutureExt, LocalBoxFuture};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use stats_api::model::{GameContent, Schedule, Team};
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+    time::{self, Instant},
+};
+
+/// Every call made for game data, so games can be run against something other
+/// than the live NHL api
+pub trait StatsSource {
+    fn get_teams(&self) -> LocalBoxFuture<'_, Result<Vec<Team>, Error>>;
+
+    fn get_team(&self, team_id: u32) -> LocalBoxFuture<'_, Result<Team, Error>>;
+
+    fn get_schedule_for(&self, date: NaiveDate) -> LocalBoxFuture<'_, Result<Schedule, Error>>;
+
+    fn get_game_content(&self, game_id: u64) -> LocalBoxFuture<'_, Result<GameContent, Error>>;
+
+    fn get_linescore(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Linescore, Error>>;
+
+    fn get_live_feed(&self, game_id: u64) -> LocalBoxFuture<'_, Result<LiveFeed, Error>>;
+
+    fn get_person(&self, person_id: u32) -> LocalBoxFuture<'_, Result<PersonDetail, Error>>;
+
+    fn get_broadcasts(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Vec<Broadcast>, Error>>;
+
+    /// Wait for `duration` of game time to pass
+    fn sleep(&self, duration: time::Duration) -> LocalBoxFuture<'_, ()> {
+        task::sleep(duration).boxed_local()
+    }
+
+    /// What time it is for games from this source
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The live NHL api
+pub struct LiveSource {
+    stats_client: stats_api::Client,
+    feed_client: feed::Client,
+}
+
+impl LiveSource {
+    pub fn new() -> Self {
+        LiveSource {
+            stats_client: stats_api::Client::new(),
+            feed_client: feed::Client::new(),
+        }
+    }
+}
+
+impl StatsSource for LiveSource {
+    fn get_teams(&self) -> LocalBoxFuture<'_, Result<Vec<Team>, Error>> {
+        self.stats_client.get_teams().boxed_local()
+    }
+
+    fn get_team(&self, team_id: u32) -> LocalBoxFuture<'_, Result<Team, Error>> {
+        self.stats_client.get_team(team_id).boxed_local()
+    }
+
+    fn get_schedule_for(&self, date: NaiveDate) -> LocalBoxFuture<'_, Result<Schedule, Error>> {
+        self.stats_client.get_schedule_for(date).boxed_local()
+    }
+
+    fn get_game_content(&self, game_id: u64) -> LocalBoxFuture<'_, Result<GameContent, Error>> {
+        self.stats_client.get_game_content(game_id).boxed_local()
+    }
+
+    fn get_linescore(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Linescore, Error>> {
+        self.feed_client.get_linescore(game_id).boxed_local()
+    }
+
+    fn get_live_feed(&self, game_id: u64) -> LocalBoxFuture<'_, Result<LiveFeed, Error>> {
+        self.feed_client.get_live_feed(game_id).boxed_local()
+    }
+
+    fn get_person(&self, person_id: u32) -> LocalBoxFuture<'_, Result<PersonDetail, Error>> {
+        self.feed_client.get_person(person_id).boxed_local()
+    }
+
+    fn get_broadcasts(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Vec<Broadcast>, Error>> {
+        self.feed_client.get_broadcasts(game_id).boxed_local()
+    }
+}
+
+/// One response in a recording, stored one per line as JSON
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub time: DateTime<Utc>,
+    /// Which call this responds to, ie. "content/2019020420"
+    pub call: String,
+    pub response: serde_json::Value,
+}
+
+/// Passes calls through to another source, saving each response with the
+/// time it was received so the game can be replayed later
+pub struct Recorder<S> {
+    source: S,
+    file: Mutex<File>,
+}
+
+impl<S: StatsSource> Recorder<S> {
+    pub fn new(source: S, path: &Path) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context(format_err!("Could not open recording {:?}", path))?;
+
+        Ok(Recorder {
+            source,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn recorded<'a, T, F>(
+        &'a self,
+        call: String,
+        response: F,
+    ) -> LocalBoxFuture<'a, Result<T, Error>>
+    where
+        T: Serialize + 'a,
+        F: Future<Output = Result<T, Error>> + 'a,
+    {
+        async move {
+            let response = response.await?;
+
+            // A broken recording shouldn't stop the game being notified
+            if let Err(e) = self.record(call, &response) {
+                log::error!("Could not record response: {}", e);
+            }
+
+            Ok(response)
+        }
+        .boxed_local()
+    }
+
+    fn record<T: Serialize>(&self, call: String, response: &T) -> Result<(), Error> {
+        let entry = Entry {
+            time: Utc::now(),
+            call,
+            response: serde_json::to_value(response)?,
+        };
+
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        self.file.lock().unwrap().write_all(&line)?;
+
+        Ok(())
+    }
+}
+
+impl<S: StatsSource> StatsSource for Recorder<S> {
+    fn get_teams(&self) -> LocalBoxFuture<'_, Result<Vec<Team>, Error>> {
+        self.recorded(String::from("teams"), self.source.get_teams())
+    }
+
+    fn get_team(&self, team_id: u32) -> LocalBoxFuture<'_, Result<Team, Error>> {
+        self.recorded(format!("team/{}", team_id), self.source.get_team(team_id))
+    }
+
+    fn get_schedule_for(&self, date: NaiveDate) -> LocalBoxFuture<'_, Result<Schedule, Error>> {
+        self.recorded(
+            format!("schedule/{}", date),
+            self.source.get_schedule_for(date),
+        )
+    }
+
+    fn get_game_content(&self, game_id: u64) -> LocalBoxFuture<'_, Result<GameContent, Error>> {
+        self.recorded(
+            format!("content/{}", game_id),
+            self.source.get_game_content(game_id),
+        )
+    }
+
+    fn get_linescore(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Linescore, Error>> {
+        self.recorded(
+            format!("linescore/{}", game_id),
+            self.source.get_linescore(game_id),
+        )
+    }
+
+    fn get_live_feed(&self, game_id: u64) -> LocalBoxFuture<'_, Result<LiveFeed, Error>> {
+        self.recorded(
+            format!("feed/{}", game_id),
+            self.source.get_live_feed(game_id),
+        )
+    }
+
+    fn get_person(&self, person_id: u32) -> LocalBoxFuture<'_, Result<PersonDetail, Error>> {
+        self.recorded(
+            format!("person/{}", person_id),
+            self.source.get_person(person_id),
+        )
+    }
+
+    fn get_broadcasts(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Vec<Broadcast>, Error>> {
+        self.recorded(
+            format!("broadcasts/{}", game_id),
+            self.source.get_broadcasts(game_id),
+        )
+    }
+
+    fn sleep(&self, duration: time::Duration) -> LocalBoxFuture<'_, ()> {
+        self.source.sleep(duration)
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.source.now()
+    }
+}
+
+/// Feeds a recording back as if it were happening now. Time in the recording
+/// starts from its first response and runs `speed` times faster than real
+/// time. Each call gets the last response recorded for it by that point, or
+/// its first response if it hadn't been recorded yet.
+pub struct Replayer {
+    responses: HashMap<String, Vec<(DateTime<Utc>, serde_json::Value)>>,
+    recording_start: DateTime<Utc>,
+    clock: ReplayClock,
+}
+
+/// Where a replay is up to in the recording
+enum ReplayClock {
+    /// `speed` times faster than real time since the replay started
+    Real { start: Instant, speed: f64 },
+    /// Only moves when a game sleeps, so a replay runs as fast as it can and
+    /// always sees the same responses
+    #[cfg(test)]
+    Stepped(std::cell::Cell<Duration>),
+}
+
+impl Replayer {
+    pub fn new(path: &Path, speed: f64) -> Result<Self, Error> {
+        let contents =
+            fs::read_to_string(path).context(format_err!("Could not read recording {:?}", path))?;
+
+        let mut entries = vec![];
+        for (idx, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: Entry = serde_json::from_str(line)
+                .context(format_err!("Invalid recording entry on line {}", idx + 1))?;
+            entries.push(entry);
+        }
+
+        let replayer = Replayer::from_entries(entries, speed)
+            .context(format_err!("Could not replay {:?}", path))?;
+
+        log::info!(
+            "Replaying {:?} from {} at {}x speed",
+            path,
+            replayer.recording_start.to_rfc2822(),
+            speed
+        );
+
+        Ok(replayer)
+    }
+
+    /// Replay entries in the order they were recorded
+    pub fn from_entries(entries: Vec<Entry>, speed: f64) -> Result<Self, Error> {
+        if speed <= 0.0 {
+            bail!("Replay speed must be greater than 0");
+        }
+
+        let recording_start = match entries.first() {
+            Some(entry) => entry.time,
+            None => bail!("Recording is empty"),
+        };
+
+        let mut responses: HashMap<String, Vec<(DateTime<Utc>, serde_json::Value)>> =
+            HashMap::new();
+        for entry in entries {
+            responses
+                .entry(entry.call)
+                .or_default()
+                .push((entry.time, entry.response));
+        }
+
+        Ok(Replayer {
+            responses,
+            recording_start,
+            clock: ReplayClock::Real {
+                start: Instant::now(),
+                speed,
+            },
+        })
+    }
+
+    /// Replay entries on a clock that only moves when a game sleeps
+    #[cfg(test)]
+    pub fn stepped(entries: Vec<Entry>) -> Result<Self, Error> {
+        let mut replayer = Replayer::from_entries(entries, 1.0)?;
+        replayer.clock = ReplayClock::Stepped(std::cell::Cell::new(Duration::zero()));
+        Ok(replayer)
+    }
+
+    fn response<T: DeserializeOwned>(&self, call: &str) -> Result<T, Error> {
+        let responses = match self.responses.get(call) {
+            Some(responses) => responses,
+            None => bail!("No response recorded for {}", call),
+        };
+
+        let now = self.now();
+        let (_, response) = responses
+            .iter()
+            .take_while(|(time, _)| *time <= now)
+            .last()
+            .unwrap_or(&responses[0]);
+
+        let response = serde_json::from_value(response.clone())
+            .context(format_err!("Invalid response recorded for {}", call))?;
+
+        Ok(response)
+    }
+
+    fn replay<T: DeserializeOwned + 'static>(
+        &self,
+        call: &str,
+    ) -> LocalBoxFuture<'_, Result<T, Error>> {
+        future::ready(self.response(call)).boxed_local()
+    }
+}
+
+impl StatsSource for Replayer {
+    fn get_teams(&self) -> LocalBoxFuture<'_, Result<Vec<Team>, Error>> {
+        self.replay("teams")
+    }
+
+    fn get_team(&self, team_id: u32) -> LocalBoxFuture<'_, Result<Team, Error>> {
+        self.replay(&format!("team/{}", team_id))
+    }
+
+    /// The recording's schedule is replayed whatever the date asked for, since
+    /// replays don't happen on the day they were recorded
+    fn get_schedule_for(&self, date: NaiveDate) -> LocalBoxFuture<'_, Result<Schedule, Error>> {
+        let call = format!("schedule/{}", date);
+        if self.responses.contains_key(&call) {
+            return self.replay(&call);
+        }
+
+        let mut recorded: Vec<&String> = self
+            .responses
+            .keys()
+            .filter(|call| call.starts_with("schedule/"))
+            .collect();
+        recorded.sort();
+        match recorded.first() {
+            Some(call) => self.replay(call),
+            None => self.replay(&call),
+        }
+    }
+
+    fn get_game_content(&self, game_id: u64) -> LocalBoxFuture<'_, Result<GameContent, Error>> {
+        self.replay(&format!("content/{}", game_id))
+    }
+
+    fn get_linescore(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Linescore, Error>> {
+        self.replay(&format!("linescore/{}", game_id))
+    }
+
+    fn get_live_feed(&self, game_id: u64) -> LocalBoxFuture<'_, Result<LiveFeed, Error>> {
+        self.replay(&format!("feed/{}", game_id))
+    }
+
+    fn get_person(&self, person_id: u32) -> LocalBoxFuture<'_, Result<PersonDetail, Error>> {
+        self.replay(&format!("person/{}", person_id))
+    }
+
+    fn get_broadcasts(&self, game_id: u64) -> LocalBoxFuture<'_, Result<Vec<Broadcast>, Error>> {
+        self.replay(&format!("broadcasts/{}", game_id))
+    }
+
+    /// Sleeps are `speed` times shorter than they would be live
+    fn sleep(&self, duration: time::Duration) -> LocalBoxFuture<'_, ()> {
+        match &self.clock {
+            ReplayClock::Real { speed, .. } => task::sleep(duration.div_f64(*speed)).boxed_local(),
+            #[cfg(test)]
+            ReplayClock::Stepped(elapsed) => {
+                let duration = Duration::from_std(duration).unwrap_or_else(|_| Duration::zero());
+                elapsed.set(elapsed.get() + duration);
+                task::yield_now().boxed_local()
+            }
+        }
+    }
+
+    /// Where the replay is up to in recording time
+    fn now(&self) -> DateTime<Utc> {
+        match &self.clock {
+            ReplayClock::Real { start, speed } => {
+                let elapsed = start.elapsed().as_secs_f64() * speed;
+                self.recording_start + Duration::milliseconds((elapsed * 1000.0) as i64)
+            }
+            #[cfg(test)]
+            ReplayClock::Stepped(elapsed) => self.recording_start + elapsed.get(),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use failure::{format_err, Error, ResultExt};
+use log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// Per-game state saved as JSON under the state dir, so a restarted process
+/// can resume a game without re-sending notifications
+pub struct StateStore {
+    dir: PathBuf,
+}
+
+impl StateStore {
+    pub fn new(dir: &Path) -> Self {
+        StateStore {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn path(&self, game_id: u64) -> PathBuf {
+        self.dir.join(format!("game-{}.json", game_id))
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, game_id: u64) -> Result<Option<T>, Error> {
+        let path = self.path(game_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path).context(format_err!("Could not read {:?}", path))?;
+        let state =
+            serde_json::from_slice(&bytes).context(format_err!("Could not parse {:?}", path))?;
+
+        Ok(Some(state))
+    }
+
+    /// Write to a temp file first and rename it over the old state, so a crash
+    /// mid-write never leaves a truncated file behind
+    pub fn save<T: Serialize>(&self, game_id: u64, state: &T) -> Result<(), Error> {
+        fs::create_dir_all(&self.dir)
+            .context(format_err!("Could not create state dir {:?}", self.dir))?;
+
+        let path = self.path(game_id);
+        let tmp_path = path.with_extension("json.tmp");
+
+        let bytes = serde_json::to_vec(state)?;
+        fs::write(&tmp_path, bytes).context(format_err!("Could not write {:?}", tmp_path))?;
+        fs::rename(&tmp_path, &path).context(format_err!("Could not write {:?}", path))?;
+
+        Ok(())
+    }
+
+    /// Remove state for games last updated more than `max_age` ago. Anything
+    /// else in the state dir is left alone.
+    pub fn prune(&self, max_age: Duration) -> Result<(), Error> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+
+        let entries =
+            fs::read_dir(&self.dir).context(format_err!("Could not read {:?}", self.dir))?;
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(e) => {
+                    warn!("Could not read entry in {:?}: {}", self.dir, e);
+                    continue;
+                }
+            };
+            if !is_state_file(&path) {
+                continue;
+            }
+            if let Err(e) = remove_if_older(&path, max_age) {
+                warn!("Could not prune {:?}: {}", path, e);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `path` is a saved game's state, or a temp file left from saving one
+fn is_state_file(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name,
+        None => return false,
+    };
+    name.starts_with("game-")
+        && (name.ends_with(".json") || name.ends_with(".json.tmp"))
+        && path.is_file()
+}
+
+fn remove_if_older(path: &Path, max_age: Duration) -> Result<(), Error> {
+    let modified = fs::metadata(path)?.modified()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    if age > max_age {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_only_removes_game_state() {
+        let dir = std::env::temp_dir().join(format!("nhl-notifier-prune-{}", std::process::id()));
+        fs::create_dir_all(dir.join("game-3.json")).unwrap();
+        for name in &["game-1.json", "game-2.json.tmp", "history.db", "notes.json"] {
+            fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        // Everything is older than nothing once a little time has passed
+        std::thread::sleep(Duration::from_millis(20));
+        StateStore::new(&dir).prune(Duration::from_secs(0)).unwrap();
+
+        let mut left: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["game-3.json", "history.db", "notes.json"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
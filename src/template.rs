@@ -0,0 +1,407 @@
+use crate::locale::Locale;
+use failure::{bail, Error};
+use serde::Deserialize;
+
+/// Placeholders every message can use
+const SCORE_PLACEHOLDERS: &[&str] = &[
+    "home_name",
+    "away_name",
+    "home_abbr",
+    "away_abbr",
+    "score_home",
+    "score_away",
+];
+
+/// Messages whose text can be set with a template
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageKind {
+    Preview,
+    PuckDrop,
+    Goal,
+    Disallowed,
+    ScoringChange,
+    ScoreCorrection,
+    Highlight,
+    HighlightFollowUp,
+    Final,
+    PlayerPoint,
+}
+
+impl MessageKind {
+    /// Name as written in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageKind::Preview => "preview",
+            MessageKind::PuckDrop => "puck_drop",
+            MessageKind::Goal => "goal",
+            MessageKind::Disallowed => "disallowed",
+            MessageKind::ScoringChange => "scoring_change",
+            MessageKind::ScoreCorrection => "score_correction",
+            MessageKind::Highlight => "highlight",
+            MessageKind::HighlightFollowUp => "highlight_follow_up",
+            MessageKind::Final => "final",
+            MessageKind::PlayerPoint => "player_point",
+        }
+    }
+
+    /// Placeholders this message has, on top of `SCORE_PLACEHOLDERS`
+    fn placeholders(self) -> &'static [&'static str] {
+        match self {
+            MessageKind::Preview => &["start_time", "subhead"],
+            MessageKind::PuckDrop => &["home_goalie", "away_goalie", "broadcasts"],
+            MessageKind::Goal => &[
+                "headline",
+                "scoring_team",
+                "other_team",
+                "period_time",
+                "period",
+                "description",
+            ],
+            MessageKind::Disallowed | MessageKind::ScoringChange => &[
+                "scoring_team",
+                "other_team",
+                "period_time",
+                "period",
+                "description",
+            ],
+            MessageKind::ScoreCorrection => &[],
+            MessageKind::Highlight => &["description", "url"],
+            MessageKind::HighlightFollowUp => &["goal_number", "description", "url"],
+            MessageKind::Final => &["winning_team", "losing_team"],
+            MessageKind::PlayerPoint => {
+                &["player", "point", "period_time", "period", "description"]
+            }
+        }
+    }
+
+    fn default_template(self, locale: Locale) -> &'static str {
+        match (locale, self) {
+            (_, MessageKind::Preview) => "{home_name} @ {away_name} - {start_time}\n\n{subhead}",
+            (Locale::En, MessageKind::PuckDrop) => {
+                "Puck drop, {home_name} vs. {away_name}\n\n\
+                 Starting goalies: {home_abbr} {home_goalie}, {away_abbr} {away_goalie}\n\n\
+                 TV: {broadcasts}"
+            }
+            (Locale::Fr, MessageKind::PuckDrop) => {
+                "Mise au jeu, {home_name} contre {away_name}\n\n\
+                 Gardiens partants : {home_abbr} {home_goalie}, {away_abbr} {away_goalie}\n\n\
+                 Télé : {broadcasts}"
+            }
+            (_, MessageKind::Goal) => {
+                "{headline}\n\n\
+                 {period_time} {period}, {home_abbr} {score_home} - {away_abbr} {score_away}\n\n\
+                 {description}"
+            }
+            (Locale::En, MessageKind::Disallowed) => {
+                "{scoring_team} goal disallowed\n\n\
+                 {period_time} {period}, {home_abbr} {score_home} - {away_abbr} {score_away}\n\n\
+                 {description}"
+            }
+            (Locale::Fr, MessageKind::Disallowed) => {
+                "But refusé aux {scoring_team}\n\n\
+                 {period_time} {period}, {home_abbr} {score_home} - {away_abbr} {score_away}\n\n\
+                 {description}"
+            }
+            (Locale::En, MessageKind::ScoringChange) => {
+                "Scoring change, {scoring_team} goal\n\n\
+                 {period_time} {period}, {home_abbr} {score_home} - {away_abbr} {score_away}\n\n\
+                 {description}"
+            }
+            (Locale::Fr, MessageKind::ScoringChange) => {
+                "Changement au pointage, but des {scoring_team}\n\n\
+                 {period_time} {period}, {home_abbr} {score_home} - {away_abbr} {score_away}\n\n\
+                 {description}"
+            }
+            (Locale::En, MessageKind::ScoreCorrection) => {
+                "Score correction\n\n{home_abbr} {score_home} - {away_abbr} {score_away}"
+            }
+            (Locale::Fr, MessageKind::ScoreCorrection) => {
+                "Correction du pointage\n\n{home_abbr} {score_home} - {away_abbr} {score_away}"
+            }
+            (Locale::En, MessageKind::Highlight) => "Highlight\n\n{description}\n\n{url}",
+            (Locale::Fr, MessageKind::Highlight) => "Faits saillants\n\n{description}\n\n{url}",
+            (Locale::En, MessageKind::HighlightFollowUp) => "Clip for goal {goal_number}: {url}",
+            (Locale::Fr, MessageKind::HighlightFollowUp) => "Vidéo du but {goal_number} : {url}",
+            (Locale::En, MessageKind::Final) => {
+                "{winning_team} win\n\n\
+                 Final score: {home_abbr} {score_home} - {away_abbr} {score_away}"
+            }
+            (Locale::Fr, MessageKind::Final) => {
+                "Victoire des {winning_team}\n\n\
+                 Pointage final : {home_abbr} {score_home} - {away_abbr} {score_away}"
+            }
+            (Locale::En, MessageKind::PlayerPoint) => {
+                "{player} {point}\n\n\
+                 {period_time} {period}, {home_abbr} {score_home} - {away_abbr} {score_away}\n\n\
+                 {description}"
+            }
+            (Locale::Fr, MessageKind::PlayerPoint) => {
+                "{player} : {point}\n\n\
+                 {period_time} {period}, {home_abbr} {score_home} - {away_abbr} {score_away}\n\n\
+                 {description}"
+            }
+        }
+    }
+
+    fn all() -> Vec<MessageKind> {
+        vec![
+            MessageKind::Preview,
+            MessageKind::PuckDrop,
+            MessageKind::Goal,
+            MessageKind::Disallowed,
+            MessageKind::ScoringChange,
+            MessageKind::ScoreCorrection,
+            MessageKind::Highlight,
+            MessageKind::HighlightFollowUp,
+            MessageKind::Final,
+            MessageKind::PlayerPoint,
+        ]
+    }
+}
+
+/// Message text for each kind of message. Unset messages use the built in
+/// text for the subscriber's locale.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Templates {
+    pub preview: Option<String>,
+    pub puck_drop: Option<String>,
+    pub goal: Option<String>,
+    pub disallowed: Option<String>,
+    pub scoring_change: Option<String>,
+    pub score_correction: Option<String>,
+    pub highlight: Option<String>,
+    pub highlight_follow_up: Option<String>,
+    #[serde(rename = "final")]
+    pub final_score: Option<String>,
+    pub player_point: Option<String>,
+}
+
+impl Templates {
+    fn get(&self, kind: MessageKind) -> Option<&String> {
+        match kind {
+            MessageKind::Preview => self.preview.as_ref(),
+            MessageKind::PuckDrop => self.puck_drop.as_ref(),
+            MessageKind::Goal => self.goal.as_ref(),
+            MessageKind::Disallowed => self.disallowed.as_ref(),
+            MessageKind::ScoringChange => self.scoring_change.as_ref(),
+            MessageKind::ScoreCorrection => self.score_correction.as_ref(),
+            MessageKind::Highlight => self.highlight.as_ref(),
+            MessageKind::HighlightFollowUp => self.highlight_follow_up.as_ref(),
+            MessageKind::Final => self.final_score.as_ref(),
+            MessageKind::PlayerPoint => self.player_point.as_ref(),
+        }
+    }
+
+    /// These templates, falling back to `other`'s where not set
+    pub fn or(&self, other: &Templates) -> Templates {
+        Templates {
+            preview: self.preview.clone().or_else(|| other.preview.clone()),
+            puck_drop: self.puck_drop.clone().or_else(|| other.puck_drop.clone()),
+            goal: self.goal.clone().or_else(|| other.goal.clone()),
+            disallowed: self.disallowed.clone().or_else(|| other.disallowed.clone()),
+            scoring_change: self
+                .scoring_change
+                .clone()
+                .or_else(|| other.scoring_change.clone()),
+            score_correction: self
+                .score_correction
+                .clone()
+                .or_else(|| other.score_correction.clone()),
+            highlight: self.highlight.clone().or_else(|| other.highlight.clone()),
+            highlight_follow_up: self
+                .highlight_follow_up
+                .clone()
+                .or_else(|| other.highlight_follow_up.clone()),
+            final_score: self
+                .final_score
+                .clone()
+                .or_else(|| other.final_score.clone()),
+            player_point: self
+                .player_point
+                .clone()
+                .or_else(|| other.player_point.clone()),
+        }
+    }
+
+    /// Check every template parses and only uses placeholders its message has
+    pub fn validate(&self) -> Result<(), Error> {
+        for kind in MessageKind::all() {
+            let template = match self.get(kind) {
+                Some(template) => template,
+                None => continue,
+            };
+
+            for part in parse(template)? {
+                if let Part::Placeholder(name) = part {
+                    let is_known =
+                        SCORE_PLACEHOLDERS.contains(&name) || kind.placeholders().contains(&name);
+                    if !is_known {
+                        let mut expected = SCORE_PLACEHOLDERS.to_vec();
+                        expected.extend(kind.placeholders());
+                        bail!(
+                            "Unknown placeholder {{{}}} in {} template, expected one of: {}",
+                            name,
+                            kind.name(),
+                            expected.join(", ")
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill in the template for `kind` with `values`, using the built in text
+    /// for `locale` if the template isn't set
+    pub fn render(&self, kind: MessageKind, locale: Locale, values: &[(&str, String)]) -> String {
+        let template = self
+            .get(kind)
+            .map(String::as_str)
+            .unwrap_or_else(|| kind.default_template(locale));
+
+        // Templates are validated at load, so this only fails for a bad default
+        let parts = match parse(template) {
+            Ok(parts) => parts,
+            Err(_) => return template.to_owned(),
+        };
+
+        let mut message = String::new();
+        for part in parts {
+            match part {
+                Part::Text(text) => message.push_str(&text),
+                Part::Placeholder(name) => {
+                    match values.iter().find(|(placeholder, _)| *placeholder == name) {
+                        Some((_, value)) => message.push_str(value),
+                        None => {
+                            message.push('{');
+                            message.push_str(name);
+                            message.push('}');
+                        }
+                    }
+                }
+            }
+        }
+        message
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Text(String),
+    Placeholder(&'a str),
+}
+
+/// Split a template into text and `{placeholder}`s. `{{` and `}}` are literal
+/// braces.
+fn parse(template: &str) -> Result<Vec<Part<'_>>, Error> {
+    let mut parts = vec![];
+    let mut text = String::new();
+    let mut rest = template;
+
+    while let Some(idx) = rest.find(&['{', '}'][..]) {
+        text.push_str(&rest[..idx]);
+        let brace = &rest[idx..idx + 1];
+        rest = &rest[idx + 1..];
+
+        if rest.starts_with(brace) {
+            text.push_str(brace);
+            rest = &rest[1..];
+            continue;
+        }
+        if brace == "}" {
+            bail!("Unmatched }} in template, use }}}} for a literal brace");
+        }
+
+        let end = match rest.find('}') {
+            Some(end) => end,
+            None => bail!("Unclosed {{ in template, use {{{{ for a literal brace"),
+        };
+        if !text.is_empty() {
+            parts.push(Part::Text(text.split_off(0)));
+        }
+        parts.push(Part::Placeholder(rest[..end].trim()));
+        rest = &rest[end + 1..];
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_placeholders() {
+        assert_eq!(
+            parse("{home_abbr} {score_home} - { away_abbr }").unwrap(),
+            vec![
+                Part::Placeholder("home_abbr"),
+                Part::Text(String::from(" ")),
+                Part::Placeholder("score_home"),
+                Part::Text(String::from(" - ")),
+                Part::Placeholder("away_abbr"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_escaped_braces() {
+        assert_eq!(
+            parse("{{{home_abbr}}} {{score}}").unwrap(),
+            vec![
+                Part::Text(String::from("{")),
+                Part::Placeholder("home_abbr"),
+                Part::Text(String::from("} {score}")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_brace() {
+        let error = parse("Goal {home_abbr").unwrap_err();
+        assert!(error.to_string().starts_with("Unclosed {"));
+
+        let error = parse("Goal home_abbr}").unwrap_err();
+        assert!(error.to_string().starts_with("Unmatched }"));
+    }
+
+    #[test]
+    fn validate_unknown_placeholder() {
+        let templates = Templates {
+            goal: Some(String::from("{headline} {score_home}")),
+            final_score: Some(String::from("{winning_team} beat {loser}")),
+            ..Templates::default()
+        };
+        let error = templates.validate().unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unknown placeholder {loser} in final template"));
+
+        let templates = Templates {
+            goal: Some(String::from("{headline} {{literal}}")),
+            ..Templates::default()
+        };
+        assert!(templates.validate().is_ok());
+    }
+
+    #[test]
+    fn render_fills_placeholders() {
+        let templates = Templates {
+            final_score: Some(String::from("{{{winning_team}}} {home_abbr} {missing}")),
+            ..Templates::default()
+        };
+        let values = vec![
+            ("winning_team", String::from("Golden Knights")),
+            ("home_abbr", String::from("VGK")),
+        ];
+        assert_eq!(
+            templates.render(MessageKind::Final, Locale::En, &values),
+            "{Golden Knights} VGK {missing}"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    config::{AppConfig, EventKind, HighlightMode, Preferences, Subscriber, TeamRef},
+    game::{resolve_player_subscriptions, sample_goal_message},
+    locale::Locale,
+    notifier::Notifier,
+    source::StatsSource,
+};
+use failure::{bail, Error};
+use stats_api::model::Team;
+
+/// Who a test notification goes to
+pub enum Recipients {
+    Number(String),
+    /// Everyone subscribed to the team, or to one of its players
+    Team(TeamRef),
+    /// Everyone in config, including player subscriptions
+    All,
+}
+
+/// Send each recipient a sample goal, formatted just like a real one, and
+/// print how the provider took each message
+pub async fn send_test_notifications(
+    config: &AppConfig,
+    source: &dyn StatsSource,
+    notifier: &dyn Notifier,
+    recipients: Recipients,
+) -> Result<(), Error> {
+    let teams = source.get_teams().await?;
+    let subscriptions = config.subscriptions_as_hashmap(&teams)?;
+    let player_subscriptions = resolve_player_subscriptions(config, source).await;
+    let everyone = Subscriber::dedup(
+        subscriptions
+            .values()
+            .flatten()
+            .cloned()
+            .chain(
+                player_subscriptions
+                    .iter()
+                    .flat_map(|sub| sub.subscribers()),
+            )
+            .collect(),
+    );
+
+    let (subscribers, team_id) = match recipients {
+        Recipients::Number(number) => {
+            let subscriber = everyone
+                .into_iter()
+                .find(|sub| sub.number == number)
+                .unwrap_or_else(|| Subscriber {
+                    number,
+                    preferences: vec![Preferences {
+                        teams: vec![],
+                        events: EventKind::all(),
+                        only_if_win: false,
+                    }],
+                    timezone: None,
+                    quiet_hours: None,
+                    templates: config.templates.clone(),
+                    locale: Locale::default(),
+                    highlight_mode: HighlightMode::default(),
+                });
+            (vec![subscriber], None)
+        }
+        Recipients::Team(team) => {
+            let team_id = team.resolve(&teams)?;
+            let mut subscribers = subscriptions.get(&team_id).cloned().unwrap_or_default();
+            for sub in player_subscriptions.iter() {
+                if sub.team_id == team_id {
+                    subscribers.extend(sub.subscribers());
+                }
+            }
+            (Subscriber::dedup(subscribers), Some(team_id))
+        }
+        Recipients::All => (everyone, None),
+    };
+
+    if subscribers.is_empty() {
+        bail!("No subscribers to send a test notification to");
+    }
+
+    println!("{:<16} {:<12} SID", "RECIPIENT", "STATUS");
+    for sub in subscribers {
+        let (scoring_team, other_team) = sample_teams(&teams, &sub, team_id)?;
+        let message = sample_goal_message(&sub, scoring_team, other_team);
+
+        match notifier.send(&sub.number, &message).await {
+            Ok(delivery) => println!(
+                "{:<16} {:<12} {}",
+                sub.number,
+                delivery.status,
+                delivery.sid.as_deref().unwrap_or("-")
+            ),
+            Err(e) => println!("{:<16} {:<12} {}", sub.number, "failed", e),
+        }
+    }
+
+    Ok(())
+}
+
+/// The team the sample goal is scored for, preferring one the subscriber
+/// follows, and a team to score against
+fn sample_teams<'a>(
+    teams: &'a [Team],
+    sub: &Subscriber,
+    team_id: Option<u32>,
+) -> Result<(&'a Team, &'a Team), Error> {
+    let scoring_team_id = team_id.or_else(|| sub.teams().first().cloned());
+    let scoring_team = match scoring_team_id {
+        Some(id) => teams.iter().find(|team| team.id == id),
+        None => teams.first(),
+    };
+    let scoring_team = match scoring_team {
+        Some(team) => team,
+        None => bail!("No teams to make a sample goal with"),
+    };
+
+    let other_team = match teams
+        .iter()
+        .find(|team| team.id != scoring_team.id && !sub.follows(team.id))
+        .or_else(|| teams.iter().find(|team| team.id != scoring_team.id))
+    {
+        Some(team) => team,
+        None => bail!("No teams to make a sample goal with"),
+    };
+
+    Ok((scoring_team, other_team))
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 pub struct CreateMessageResponse {
+    pub sid: Option<String>,
     pub error_code: Option<String>,
     pub error_message: Option<String>,
     pub status: String,